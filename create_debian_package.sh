@@ -16,9 +16,9 @@ RUSTFLAGS="-C target-feature=+crt-static" cargo build --release --target x86_64-
 mkdir deb-packager/bin
 cp target/x86_64-unknown-linux-gnu/release/setup deb-packager/bin/
 
-# Copy Jsonschema file to `deb-packager/usr/share/setup/` directory
+# Generate Jsonschema file into `deb-packager/usr/share/setup/` directory
 mkdir -p deb-packager/usr/share/setup/
-cp jsonschema.json deb-packager/usr/share/setup/projectfileschema.json
+deb-packager/bin/setup schema deb-packager/usr/share/setup/projectfileschema.json
 
 # Build deb package
 dpkg-deb --root-owner-group --build deb-packager $PACKAGENAME
@@ -0,0 +1,198 @@
+//! Library interface to load projects, plan their jobs and execute them without the command line interface
+
+use std::{
+    path::Path,
+    sync::{atomic::AtomicBool, Arc},
+};
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+use crate::{
+    cli::OutputFormat,
+    events::EventStreamReporter,
+    modes::{run_job, ExecutionOptions},
+    report::{JobPlan, JobReport},
+    reporter::{MultiReporter, Reporter},
+    schema::{
+        output_mode::OutputMode,
+        project::Project,
+        validation::{Diagnostic, DiagnosticSeverity},
+    },
+    util::{import_project_value, parse_project_data, Console},
+};
+
+/// A validated project together with the path of its project file.
+/// Relative paths like inputs, outputs and the task cache are resolved against the directory of the project file.
+#[non_exhaustive]
+pub struct LoadedProject {
+    pub project: Project,
+    pub project_file: String,
+}
+
+/// Options of a job execution, created with `JobOptions::default()` and the builder methods
+#[derive(Default, Clone)]
+#[non_exhaustive]
+pub struct JobOptions {
+    /// Reporters notified about the progress of the execution, e.g. a `ConsoleReporter`
+    pub reporters: Vec<Arc<dyn Reporter>>,
+    /// Ignore the task cache and execute every task
+    pub force: bool,
+    /// Write a Chrome trace of the execution to this file
+    pub trace: Option<String>,
+    /// Write a JUnit XML report of the execution to this file
+    pub junit: Option<String>,
+    /// Stream execution events as JSON Lines to this file or file descriptor
+    pub events: Option<String>,
+    /// Overrides the output mode of the job
+    pub output_mode: Option<OutputMode>,
+    /// Stops the execution like Ctrl+C once set: no further step is started, running parallel tasks are
+    /// terminated, services are stopped and the `on_failure` and `finally` hooks are executed
+    pub interrupt: Arc<AtomicBool>,
+    /// Stops the steps of the hooks once set, like a second Ctrl+C
+    pub hook_interrupt: Arc<AtomicBool>,
+}
+
+impl JobOptions {
+    /// Adds a reporter which is notified about the progress of the execution
+    pub fn reporter(mut self, reporter: Arc<dyn Reporter>) -> JobOptions {
+        self.reporters.push(reporter);
+        self
+    }
+
+    /// Ignores the task cache and executes every task
+    pub fn force(mut self, force: bool) -> JobOptions {
+        self.force = force;
+        self
+    }
+
+    /// Writes a Chrome trace of the execution to the file
+    pub fn trace(mut self, file: impl Into<String>) -> JobOptions {
+        self.trace = Some(file.into());
+        self
+    }
+
+    /// Writes a JUnit XML report of the execution to the file
+    pub fn junit(mut self, file: impl Into<String>) -> JobOptions {
+        self.junit = Some(file.into());
+        self
+    }
+
+    /// Streams execution events as JSON Lines to the file or file descriptor
+    pub fn events(mut self, target: impl Into<String>) -> JobOptions {
+        self.events = Some(target.into());
+        self
+    }
+
+    /// Overrides the output mode of the job
+    pub fn output_mode(mut self, output_mode: OutputMode) -> JobOptions {
+        self.output_mode = Some(output_mode);
+        self
+    }
+
+    /// Uses the flag to stop the execution, e.g. from another thread
+    pub fn interrupt(mut self, interrupt: Arc<AtomicBool>) -> JobOptions {
+        self.interrupt = interrupt;
+        self
+    }
+
+    /// Uses the flag to stop the steps of the hooks, which still run after the execution was interrupted
+    pub fn hook_interrupt(mut self, hook_interrupt: Arc<AtomicBool>) -> JobOptions {
+        self.hook_interrupt = hook_interrupt;
+        self
+    }
+}
+
+impl LoadedProject {
+    /// Loads a project file. Files with a `.yaml` or `.yml` extension are parsed as YAML, all others as JSON.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<LoadedProject> {
+        let project_file = path.as_ref().to_string_lossy().to_string();
+        let value = import_project_value(&project_file)?;
+        Self::from_value(value, path)
+    }
+
+    /// Loads a project from a JSON string, `project_file` is the path the project is assumed to live at
+    pub fn from_json_str(content: &str, project_file: impl AsRef<Path>) -> Result<LoadedProject> {
+        let source = project_file.as_ref().to_string_lossy().to_string();
+        Self::from_value(parse_project_data(content, false, &source)?, project_file)
+    }
+
+    /// Loads a project from a YAML string, `project_file` is the path the project is assumed to live at
+    pub fn from_yaml_str(content: &str, project_file: impl AsRef<Path>) -> Result<LoadedProject> {
+        let source = project_file.as_ref().to_string_lossy().to_string();
+        Self::from_value(parse_project_data(content, true, &source)?, project_file)
+    }
+
+    /// Loads a project from a JSON value, `project_file` is the path the project is assumed to live at
+    pub fn from_value(value: Value, project_file: impl AsRef<Path>) -> Result<LoadedProject> {
+        Ok(LoadedProject {
+            project: Project::import_project(value)?,
+            project_file: project_file.as_ref().to_string_lossy().to_string(),
+        })
+    }
+
+    /// Returns the semantic warnings and errors of the project
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        self.project.semantic_diagnostics()
+    }
+
+    /// Resolves a job into the list of steps which would be executed, without executing anything
+    pub fn plan_job(&self, job: &str) -> Result<JobPlan> {
+        self.check_errors()?;
+        run_job::plan_job(
+            &self.project,
+            &self.project_file,
+            job,
+            &Self::execution_options(&JobOptions::default()),
+            Arc::new(MultiReporter::default()),
+        )
+    }
+
+    /// Executes a job. A failing job is not an error, it is reported through `success` and `error` of the report.
+    pub fn execute_job(&self, job: &str, options: &JobOptions) -> Result<JobReport> {
+        self.check_errors()?;
+        let mut reporter = MultiReporter::new(options.reporters.clone());
+        if let Some(events_target) = &options.events {
+            reporter.push(Arc::new(EventStreamReporter::open(events_target)?));
+        }
+        run_job::execute_job(
+            &self.project,
+            &self.project_file,
+            job,
+            &Self::execution_options(options),
+            Arc::new(reporter),
+        )
+    }
+
+    /// Fails with all semantic errors of the project
+    fn check_errors(&self) -> Result<()> {
+        let errors: Vec<String> = self
+            .diagnostics()
+            .into_iter()
+            .filter(|diagnostic| diagnostic.severity == DiagnosticSeverity::Error)
+            .map(|diagnostic| diagnostic.message)
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!(errors.join("\n")))
+        }
+    }
+
+    /// Maps the library options to the options of the execution modes
+    fn execution_options(options: &JobOptions) -> ExecutionOptions {
+        ExecutionOptions {
+            silent_children: false,
+            dry_run: false,
+            force: options.force,
+            output: OutputFormat::Text,
+            trace: options.trace.clone(),
+            junit: options.junit.clone(),
+            events: options.events.clone(),
+            output_mode: options.output_mode,
+            console: Console::default(),
+            interrupt: options.interrupt.clone(),
+            hook_interrupt: options.hook_interrupt.clone(),
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use std::{
+    fmt::Display,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Result};
+
+use crate::{schema::builtin::Builtin, util::expand_globs};
+
+/// Single change to the file system performed by a built-in task
+pub enum FileAction {
+    Copy { from: PathBuf, to: PathBuf },
+    Remove(PathBuf),
+    CreateDir(PathBuf),
+    Symlink { target: String, link: PathBuf },
+    WriteFile { path: PathBuf, content: String },
+}
+
+impl Display for FileAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileAction::Copy { from, to } => {
+                write!(f, "copy {} to {}", from.display(), to.display())
+            }
+            FileAction::Remove(path) => write!(f, "remove {}", path.display()),
+            FileAction::CreateDir(path) => write!(f, "create directory {}", path.display()),
+            FileAction::Symlink { target, link } => {
+                write!(f, "link {} to {}", link.display(), target)
+            }
+            FileAction::WriteFile { path, content } => {
+                write!(f, "write {} bytes to {}", content.len(), path.display())
+            }
+        }
+    }
+}
+
+impl FileAction {
+    /// Performs the change to the file system
+    pub fn apply(&self) -> Result<()> {
+        let result = match self {
+            FileAction::Copy { from, to }
+                if from.is_dir()
+                    && !from.is_symlink()
+                    && resolve_path(to).starts_with(resolve_path(from)) =>
+            {
+                Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "the destination is inside the source",
+                ))
+            }
+            FileAction::Copy { from, to } => copy_recursive(from, to),
+            FileAction::Remove(path) => remove_path(path),
+            FileAction::CreateDir(path) => std::fs::create_dir_all(path),
+            FileAction::Symlink { target, link } => {
+                // Existing links and files are replaced like with `ln -sf`
+                if link.is_symlink() || link.is_file() {
+                    std::fs::remove_file(link)?;
+                }
+                std::os::unix::fs::symlink(target, link)
+            }
+            FileAction::WriteFile { path, content } => {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(path, content)
+            }
+        };
+
+        result.map_err(|error| anyhow!(format!("Cannot {} ({})", self, error)))
+    }
+}
+
+/// Resolves the arguments of a built-in task into the changes to the file system without performing them.
+/// Relative paths and globs are resolved against the working directory.
+pub fn file_actions(builtin: Builtin, args: &[String], work_dir: &Path) -> Result<Vec<FileAction>> {
+    let check_arg_count = |valid: bool, expected: &str| {
+        if valid {
+            Ok(())
+        } else {
+            Err(anyhow!(format!(
+                "Built-in operation \"{}\" requires {} but {} were provided",
+                builtin.name(),
+                expected,
+                args.len()
+            )))
+        }
+    };
+
+    match builtin {
+        Builtin::Copy => {
+            check_arg_count(args.len() >= 2, "at least 2 arguments")?;
+            let (destination_arg, source_globs) = args.split_last().unwrap();
+            let sources = expand_globs(work_dir, source_globs)?;
+            if sources.is_empty() {
+                return Err(anyhow!(format!(
+                    "No files match {}",
+                    source_globs.join(", ")
+                )));
+            }
+
+            // A single source is copied to the destination unless it is a directory
+            let destination = work_dir.join(destination_arg);
+            if sources.len() == 1 && !destination.is_dir() && !destination_arg.ends_with('/') {
+                return Ok(vec![FileAction::Copy {
+                    from: sources[0].clone(),
+                    to: destination,
+                }]);
+            }
+
+            Ok(sources
+                .into_iter()
+                .map(|from| {
+                    let to = destination.join(from.file_name().unwrap_or_default());
+                    FileAction::Copy { from, to }
+                })
+                .collect())
+        }
+        Builtin::Remove => {
+            check_arg_count(!args.is_empty(), "at least 1 argument")?;
+            Ok(expand_globs(work_dir, args)?
+                .into_iter()
+                .map(FileAction::Remove)
+                .collect())
+        }
+        Builtin::Mkdir => {
+            check_arg_count(!args.is_empty(), "at least 1 argument")?;
+            Ok(args
+                .iter()
+                .map(|path| FileAction::CreateDir(work_dir.join(path)))
+                .collect())
+        }
+        Builtin::Symlink => {
+            check_arg_count(args.len() == 2, "exactly 2 arguments")?;
+            Ok(vec![FileAction::Symlink {
+                target: args[0].clone(),
+                link: work_dir.join(&args[1]),
+            }])
+        }
+        Builtin::WriteFile => {
+            check_arg_count(args.len() == 2, "exactly 2 arguments")?;
+            Ok(vec![FileAction::WriteFile {
+                path: work_dir.join(&args[0]),
+                content: args[1].clone(),
+            }])
+        }
+    }
+}
+
+/// Resolves symbolic links and `..` in the existing part of the path. Components which do not exist yet
+/// are appended unchanged.
+fn resolve_path(path: &Path) -> PathBuf {
+    let mut missing = Vec::new();
+    let mut existing = path;
+    loop {
+        if let Ok(resolved) = existing.canonicalize() {
+            return missing
+                .into_iter()
+                .rev()
+                .fold(resolved, |path, name| path.join(name));
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name);
+                existing = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+/// Removes a file, link or directory with all its contents. Paths which no longer exist, e.g. because a
+/// parent directory matched by the same glob was removed first, are ignored like with `rm -rf`.
+fn remove_path(path: &Path) -> std::io::Result<()> {
+    let result = match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.is_dir() => std::fs::remove_dir_all(path),
+        Ok(_) => std::fs::remove_file(path),
+        Err(error) => Err(error),
+    };
+    match result {
+        Err(error) if error.kind() == ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+/// Copies a file or a directory with all its contents, creating missing parent directories.
+/// Symbolic links are copied as links instead of following them, like with `cp -r`.
+fn copy_recursive(from: &Path, to: &Path) -> std::io::Result<()> {
+    let metadata = std::fs::symlink_metadata(from)?;
+    if metadata.is_dir() {
+        std::fs::create_dir_all(to)?;
+        for entry in std::fs::read_dir(from)? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        return Ok(());
+    }
+
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    if metadata.is_symlink() {
+        if to.is_symlink() || to.is_file() {
+            std::fs::remove_file(to)?;
+        }
+        return std::os::unix::fs::symlink(std::fs::read_link(from)?, to);
+    }
+    std::fs::copy(from, to).map(|_| ())
+}
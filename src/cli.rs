@@ -1,4 +1,9 @@
-use clap::{Parser, Subcommand};
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
+
+use crate::{
+    schema::output_mode::OutputMode,
+    util::{Console, Verbosity},
+};
 
 #[derive(Parser)]
 #[clap(author, version, about)]
@@ -10,10 +15,69 @@ pub struct CliParameters {
     #[clap(short, long)]
     pub silent_children: Option<bool>,
 
+    /// Output format of the mode results. With `json`, all other messages are printed to stderr.
+    #[clap(short, long, value_enum, global = true)]
+    pub output: Option<OutputFormat>,
+
+    /// Only prints warnings, errors and the output of child processes
+    #[clap(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// Prints additional details about the execution. Use twice for debug details.
+    #[clap(short, long, action = ArgAction::Count, global = true)]
+    pub verbose: u8,
+
+    /// When to color the output. `auto` colors streams connected to a terminal, unless `NO_COLOR` is set.
+    #[clap(long, value_enum, global = true)]
+    pub color: Option<ColorMode>,
+
     #[clap(subcommand)]
     pub mode: Mode,
 }
 
+impl CliParameters {
+    /// Returns the arguments changing the console output, to pass them on to nested runs of `setup`
+    pub fn output_args(&self) -> Vec<String> {
+        let mut args: Vec<String> = Vec::new();
+        if let Some(silent_children) = self.silent_children {
+            args.extend([
+                String::from("--silent-children"),
+                silent_children.to_string(),
+            ]);
+        }
+        if self.quiet {
+            args.push(String::from("--quiet"));
+        }
+        if self.verbose > 0 {
+            args.push(format!("-{}", "v".repeat(self.verbose as usize)));
+        }
+        if let Some(color) = self.color {
+            let color = color.to_possible_value().expect("No skipped color modes");
+            args.extend([String::from("--color"), color.get_name().to_string()]);
+        }
+        args
+    }
+
+    /// Returns the settings of the console output of this run
+    pub fn console(&self) -> Console {
+        let output = self.output.unwrap_or(OutputFormat::Text);
+        let verbosity = match (self.quiet, self.verbose) {
+            (true, _) => Verbosity::Quiet,
+            (false, 0) => Verbosity::Normal,
+            (false, 1) => Verbosity::Verbose,
+            (false, _) => Verbosity::Debug,
+        };
+        Console {
+            // Keep stdout free for the json output and completion names
+            messages_to_stderr: output == OutputFormat::Json
+                || matches!(self.mode, Mode::CompleteNames { .. }),
+            verbosity,
+            ..Console::default()
+        }
+        .with_color_mode(self.color.unwrap_or(ColorMode::Auto))
+    }
+}
+
 #[derive(Subcommand)]
 pub enum Mode {
     /// Check whether the project is valid in regards to the schema
@@ -22,6 +86,30 @@ pub enum Mode {
     Run {
         /// Name of the job to execute
         job: String,
+
+        /// Prints the execution plan without executing any task
+        #[clap(long)]
+        dry_run: bool,
+
+        /// Executes all tasks, even if they are up to date
+        #[clap(long)]
+        force: bool,
+
+        /// Writes a Chrome trace of the execution to the file, viewable in Perfetto or chrome://tracing
+        #[clap(long, value_name = "FILE")]
+        trace: Option<String>,
+
+        /// Writes a JUnit XML report of the job to the file
+        #[clap(long, value_name = "FILE")]
+        junit: Option<String>,
+
+        /// Writes execution events as JSON Lines to the file or to an open file descriptor given as number
+        #[clap(long, value_name = "PATH|FD")]
+        events: Option<String>,
+
+        /// Overrides how the output of the tasks is printed, see `output_mode` of the job
+        #[clap(long, value_enum)]
+        output_mode: Option<OutputMode>,
     },
     /// Execute a single task from the project
     RunTask {
@@ -31,9 +119,114 @@ pub enum Mode {
         /// Additional arguments for the task
         arguments: Vec<String>,
 
+        /// Prints the execution plan without executing the task
+        #[clap(long)]
+        dry_run: bool,
+
+        /// Executes the task, even if it is up to date
+        #[clap(long)]
+        force: bool,
+
+        /// Reruns the task whenever watched files change
+        #[clap(long, conflicts_with = "dry_run")]
+        watch: bool,
+
+        /// Clears the screen before each rerun in watch mode
+        #[clap(long, requires = "watch")]
+        clear: bool,
+    },
+    /// Executes a job from the project and reruns it whenever watched files change
+    Watch {
+        /// Name of the job to execute
+        job: String,
+
+        /// Clears the screen before each rerun
+        #[clap(long)]
+        clear: bool,
     },
     /// Lists all available jobs in the project
-    ListJobs,
+    ListJobs {
+        /// Only lists jobs with the given tag
+        #[clap(long)]
+        tag: Option<String>,
+    },
     /// Lists all available tasks in the project
-    ListTasks,
+    ListTasks {
+        /// Only lists tasks with the given tag
+        #[clap(long)]
+        tag: Option<String>,
+
+        /// Also lists hidden tasks
+        #[clap(short, long)]
+        all: bool,
+    },
+    /// Creates a new project file. Uses the given project file path or `Setup.json`/`Setup.yaml`
+    Init {
+        /// Format of the created project file
+        #[clap(long, value_enum, default_value_t = ProjectFormat::Json)]
+        format: ProjectFormat,
+
+        /// Detects common ecosystems in the project directory and adds matching tasks
+        #[clap(short, long)]
+        detect: bool,
+
+        /// Overwrites an already existing project file
+        #[clap(short, long)]
+        force: bool,
+    },
+    /// Generates a shell completion script with dynamic completion of job and task names
+    Completions {
+        /// Shell to generate the completion script for
+        #[clap(value_enum)]
+        shell: CompletionShell,
+    },
+    /// Prints the names of all jobs or tasks of the project, one per line. Used by shell completions
+    #[clap(hide = true)]
+    CompleteNames {
+        /// Kind of names to print
+        #[clap(value_enum)]
+        kind: NameKind,
+    },
+    /// Prints the json schema of the project file
+    Schema {
+        /// Path of the file to write the schema to. Prints the schema if omitted
+        file: Option<String>,
+    },
+}
+
+/// File format of a project file
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectFormat {
+    Json,
+    Yaml,
+}
+
+/// Output format of mode results
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+/// When to color the console output
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
+/// Shells supported by completions mode
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// Kind of names printed for shell completions
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum NameKind {
+    Jobs,
+    Tasks,
 }
@@ -0,0 +1,248 @@
+use std::{
+    fs::File,
+    io::Write,
+    os::fd::BorrowedFd,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::SystemTime,
+};
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+
+use crate::{
+    report::{unix_secs, JobReport, TaskReport, TaskStatus},
+    reporter::{Reporter, TaskInfo},
+};
+
+/// Version of the event schema. Increased on incompatible changes, new events and fields may be added anytime.
+pub const EVENTS_VERSION: u32 = 1;
+
+/// Counter used to identify tasks within the event stream
+static NEXT_TASK_ID: AtomicUsize = AtomicUsize::new(1);
+
+/// Output stream of a task
+#[derive(Serialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// Single event of the execution lifecycle
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum Event<'a> {
+    JobStarted {
+        job: &'a str,
+    },
+    TaskSpawned {
+        task_id: usize,
+        task: &'a str,
+        label: Option<&'a str>,
+        command: &'a str,
+        pid: Option<u32>,
+    },
+    TaskSkipped {
+        task_id: usize,
+        task: &'a str,
+        label: Option<&'a str>,
+        reason: &'a str,
+    },
+    OutputLine {
+        task_id: usize,
+        stream: OutputStream,
+        line: &'a str,
+    },
+    TaskExited {
+        task_id: usize,
+        task: &'a str,
+        label: Option<&'a str>,
+        status: TaskStatus,
+        exit_code: Option<i32>,
+        signal: Option<i32>,
+        duration_secs: Option<f64>,
+    },
+    ServiceReady {
+        task_id: usize,
+        task: &'a str,
+        label: Option<&'a str>,
+    },
+    ServiceStopping {
+        task_id: usize,
+        task: &'a str,
+        label: Option<&'a str>,
+    },
+    HookStarted {
+        job: &'a str,
+        hook: &'a str,
+    },
+    HookFinished {
+        job: &'a str,
+        hook: &'a str,
+        success: bool,
+    },
+    JobFinished {
+        job: &'a str,
+        success: bool,
+        duration_secs: f64,
+    },
+}
+
+/// Line of the event stream
+#[derive(Serialize)]
+struct EventRecord<'a> {
+    version: u32,
+    timestamp: f64,
+    #[serde(flatten)]
+    event: Event<'a>,
+}
+
+/// Reporter writing every event as a single JSON line
+pub struct EventStreamReporter {
+    sink: Mutex<Box<dyn Write + Send>>,
+}
+
+impl EventStreamReporter {
+    /// Opens the event stream. A number is used as an already open file descriptor, anything else as a file path.
+    pub fn open(target: &str) -> Result<EventStreamReporter> {
+        let sink: Box<dyn Write + Send> = match target.parse::<i32>() {
+            Ok(fd) => {
+                // SAFETY: `fcntl` only queries the flags of the file descriptor
+                if unsafe { libc::fcntl(fd, libc::F_GETFD) } == -1 {
+                    return Err(anyhow!(format!(
+                        "File descriptor {} for events is not open",
+                        fd
+                    )));
+                }
+                // SAFETY: The file descriptor was checked to be open. It is only borrowed to duplicate it, so
+                // the original descriptor stays owned by the caller.
+                let borrowed = unsafe { BorrowedFd::borrow_raw(fd) };
+                match borrowed.try_clone_to_owned() {
+                    Ok(owned) => Box::new(File::from(owned)),
+                    Err(error) => {
+                        return Err(anyhow!(format!(
+                            "Cannot use file descriptor {} for events ({})",
+                            fd, error
+                        )))
+                    }
+                }
+            }
+            Err(_) => match File::create(target) {
+                Ok(file) => Box::new(file),
+                Err(error) => {
+                    return Err(anyhow!(format!(
+                        "Cannot open events file \"{}\" ({})",
+                        target, error
+                    )))
+                }
+            },
+        };
+
+        Ok(EventStreamReporter {
+            sink: Mutex::new(sink),
+        })
+    }
+
+    /// Writes the event as a single JSON line
+    fn emit(&self, event: Event) {
+        let record = EventRecord {
+            version: EVENTS_VERSION,
+            timestamp: unix_secs(SystemTime::now()),
+            event,
+        };
+        if let Ok(line) = serde_json::to_string(&record) {
+            let mut sink = self.sink.lock().unwrap();
+            let _ = writeln!(sink, "{}", line);
+            let _ = sink.flush();
+        }
+    }
+}
+
+impl Reporter for EventStreamReporter {
+    fn job_started(&self, job: &str) {
+        self.emit(Event::JobStarted { job });
+    }
+
+    fn task_started(&self, task: &TaskInfo, pid: Option<u32>) {
+        self.emit(Event::TaskSpawned {
+            task_id: task.id,
+            task: &task.task,
+            label: task.label.as_deref(),
+            command: &task.command,
+            pid,
+        });
+    }
+
+    fn task_skipped(&self, task: &TaskInfo, reason: &str) {
+        self.emit(Event::TaskSkipped {
+            task_id: task.id,
+            task: &task.task,
+            label: task.label.as_deref(),
+            reason,
+        });
+    }
+
+    fn output_line(&self, task: &TaskInfo, stream: OutputStream, line: &str) {
+        self.emit(Event::OutputLine {
+            task_id: task.id,
+            stream,
+            line,
+        });
+    }
+
+    fn task_finished(&self, task: &TaskInfo, report: &TaskReport) {
+        self.emit(Event::TaskExited {
+            task_id: task.id,
+            task: &task.task,
+            label: task.label.as_deref(),
+            status: report.status,
+            exit_code: report.exit_code,
+            signal: report.signal,
+            duration_secs: report.duration_secs,
+        });
+    }
+
+    fn service_ready(&self, task: &TaskInfo) {
+        self.emit(Event::ServiceReady {
+            task_id: task.id,
+            task: &task.task,
+            label: task.label.as_deref(),
+        });
+    }
+
+    fn service_stopping(&self, task: &TaskInfo) {
+        self.emit(Event::ServiceStopping {
+            task_id: task.id,
+            task: &task.task,
+            label: task.label.as_deref(),
+        });
+    }
+
+    fn hook_started(&self, job: &str, hook: &str) {
+        self.emit(Event::HookStarted { job, hook });
+    }
+
+    fn hook_finished(&self, job: &str, hook: &str, error: Option<&str>) {
+        self.emit(Event::HookFinished {
+            job,
+            hook,
+            success: error.is_none(),
+        });
+    }
+
+    fn job_finished(&self, report: &JobReport) {
+        self.emit(Event::JobFinished {
+            job: &report.job,
+            success: report.success,
+            duration_secs: report.duration_secs,
+        });
+    }
+}
+
+/// Returns a new identifier for a task
+pub fn next_task_id() -> usize {
+    NEXT_TASK_ID.fetch_add(1, Ordering::SeqCst)
+}
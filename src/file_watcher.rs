@@ -0,0 +1,157 @@
+use std::{
+    collections::HashMap,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Result};
+use glob::Pattern;
+use inotify::{EventMask, Inotify, WatchDescriptor, WatchMask};
+
+/// Directories which are never watched
+pub const IGNORED_DIRS: [&str; 4] = [".git", ".setup", "node_modules", "target"];
+
+/// FileWatcher watches a directory recursively for changes of files matching the given globs.
+pub struct FileWatcher {
+    base_dir: PathBuf,
+    patterns: Vec<Pattern>,
+    inotify: Inotify,
+    watched_dirs: HashMap<WatchDescriptor, PathBuf>,
+}
+
+impl FileWatcher {
+    /// Creates a new FileWatcher. Globs are relative to the base directory.
+    /// Without any globs, all files outside of ignored directories are watched.
+    pub fn new(base_dir: &Path, globs: &[String]) -> Result<FileWatcher> {
+        let mut patterns: Vec<Pattern> = Vec::new();
+        for glob in globs {
+            match Pattern::new(glob) {
+                Ok(pattern) => patterns.push(pattern),
+                Err(error) => {
+                    return Err(anyhow!(format!(
+                        "Invalid watch glob \"{}\" ({})",
+                        glob, error
+                    )))
+                }
+            }
+        }
+
+        let inotify = match Inotify::init() {
+            Ok(inotify) => inotify,
+            Err(error) => return Err(anyhow!(format!("Cannot initialize inotify ({})", error))),
+        };
+
+        let mut watcher = FileWatcher {
+            base_dir: base_dir.to_path_buf(),
+            patterns,
+            inotify,
+            watched_dirs: HashMap::new(),
+        };
+        watcher.watch_dir_recursive(base_dir)?;
+
+        Ok(watcher)
+    }
+
+    /// Returns all changed files since the last call without blocking
+    pub fn poll_changes(&mut self) -> Result<Vec<PathBuf>> {
+        let mut changed_files: Vec<PathBuf> = Vec::new();
+        let mut new_dirs: Vec<PathBuf> = Vec::new();
+        let mut buffer = [0; 4096];
+
+        loop {
+            let events = match self.inotify.read_events(&mut buffer) {
+                Ok(events) => events,
+                Err(error) if error.kind() == ErrorKind::WouldBlock => break,
+                Err(error) => return Err(anyhow!(format!("Cannot read file events ({})", error))),
+            };
+
+            for event in events {
+                let Some(dir) = self.watched_dirs.get(&event.wd) else {
+                    continue;
+                };
+                let Some(name) = event.name else {
+                    continue;
+                };
+                let path = dir.join(name);
+
+                if event.mask.contains(EventMask::ISDIR) {
+                    if event
+                        .mask
+                        .intersects(EventMask::CREATE | EventMask::MOVED_TO)
+                        && !is_ignored_dir(&path)
+                    {
+                        new_dirs.push(path);
+                    }
+                    continue;
+                }
+
+                if self.matches(&path) {
+                    changed_files.push(path);
+                }
+            }
+        }
+
+        // Directories created after the start are watched as well
+        for dir in new_dirs {
+            self.watch_dir_recursive(&dir)?;
+        }
+
+        Ok(changed_files)
+    }
+
+    /// Checks whether the given file matches the globs
+    fn matches(&self, path: &Path) -> bool {
+        if self.patterns.is_empty() {
+            return true;
+        }
+
+        let relative_path = path.strip_prefix(&self.base_dir).unwrap_or(path);
+        self.patterns
+            .iter()
+            .any(|pattern| pattern.matches_path(relative_path))
+    }
+
+    /// Adds watches for the given directory and all of its subdirectories
+    fn watch_dir_recursive(&mut self, dir: &Path) -> Result<()> {
+        let mask = WatchMask::CREATE
+            | WatchMask::MODIFY
+            | WatchMask::CLOSE_WRITE
+            | WatchMask::DELETE
+            | WatchMask::MOVED_FROM
+            | WatchMask::MOVED_TO;
+
+        match self.inotify.watches().add(dir, mask) {
+            Ok(descriptor) => {
+                self.watched_dirs.insert(descriptor, dir.to_path_buf());
+            }
+            // The directory was removed again before it could be watched
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(()),
+            Err(error) => {
+                return Err(anyhow!(format!(
+                    "Cannot watch directory '{}' ({})",
+                    dir.display(),
+                    error
+                )))
+            }
+        }
+
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return Ok(());
+        };
+        for entry in entries.filter_map(Result::ok) {
+            let path = entry.path();
+            if path.is_dir() && !path.is_symlink() && !is_ignored_dir(&path) {
+                self.watch_dir_recursive(&path)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks whether the directory is one of the `IGNORED_DIRS`
+fn is_ignored_dir(dir: &Path) -> bool {
+    dir.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| IGNORED_DIRS.contains(&name))
+}
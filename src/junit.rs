@@ -0,0 +1,110 @@
+use anyhow::{anyhow, Result};
+
+use crate::report::{JobReport, TaskReport, TaskStatus};
+
+/// Writes the result of the job as JUnit XML with one testsuite for the job and one testcase per task
+pub fn write_junit(file: &str, report: &JobReport) -> Result<()> {
+    let hook_tasks = report
+        .hooks
+        .iter()
+        .flat_map(|hook| hook.tasks.iter().map(|task| (Some(&hook.hook), task)));
+    let tasks: Vec<(Option<&String>, &TaskReport)> = report
+        .tasks
+        .iter()
+        .map(|task| (None, task))
+        .chain(hook_tasks)
+        .collect();
+
+    let failures = tasks
+        .iter()
+        .filter(|(_, task)| task.status == TaskStatus::Failed)
+        .count();
+    let skipped = tasks
+        .iter()
+        .filter(|(_, task)| matches!(task.status, TaskStatus::Skipped | TaskStatus::NotStarted))
+        .count();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"setup\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{:.3}\">\n",
+        tasks.len(),
+        failures,
+        skipped,
+        report.duration_secs
+    ));
+    xml.push_str(&format!(
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"{}\" time=\"{:.3}\">\n",
+        escape_xml(&report.job),
+        tasks.len(),
+        failures,
+        skipped,
+        report.duration_secs
+    ));
+
+    for (hook, task) in tasks {
+        let classname = match hook {
+            Some(hook) => format!("{}.{}.{}", report.job, hook, task.task),
+            None => format!("{}.{}", report.job, task.task),
+        };
+        let name = match &task.label {
+            Some(label) => format!("{} [{}]", task.command, label),
+            None => task.command.clone(),
+        };
+        xml.push_str(&format!(
+            "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+            escape_xml(&name),
+            escape_xml(&classname),
+            task.duration_secs.unwrap_or(0.0)
+        ));
+
+        match task.status {
+            TaskStatus::Success => xml.push_str("/>\n"),
+            TaskStatus::Skipped | TaskStatus::NotStarted => {
+                let reason = match &task.skip_reason {
+                    Some(reason) => reason.clone(),
+                    None => String::from("not started"),
+                };
+                xml.push_str(&format!(
+                    ">\n      <skipped message=\"{}\"/>\n    </testcase>\n",
+                    escape_xml(&reason)
+                ));
+            }
+            TaskStatus::Failed => {
+                let message = match (task.exit_code, task.signal) {
+                    (Some(code), _) => format!("Exit code {}", code),
+                    (None, Some(signal)) => format!("Terminated by signal {}", signal),
+                    (None, None) => String::from("Failed"),
+                };
+                xml.push_str(&format!(
+                    ">\n      <failure message=\"{}\" type=\"failure\">{}</failure>\n    </testcase>\n",
+                    escape_xml(&message),
+                    escape_xml(&task.stderr_tail.join("\n"))
+                ));
+            }
+        }
+    }
+
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    match std::fs::write(file, xml) {
+        Ok(()) => Ok(()),
+        Err(error) => Err(anyhow!(format!(
+            "Cannot write JUnit report \"{}\" ({})",
+            file, error
+        ))),
+    }
+}
+
+/// Escapes the characters with a special meaning in XML
+fn escape_xml(text: &str) -> String {
+    text.chars()
+        .filter(|character| !character.is_control() || matches!(character, '\n' | '\t'))
+        .map(|character| match character {
+            '&' => String::from("&amp;"),
+            '<' => String::from("&lt;"),
+            '>' => String::from("&gt;"),
+            '"' => String::from("&quot;"),
+            '\'' => String::from("&apos;"),
+            character => character.to_string(),
+        })
+        .collect()
+}
@@ -1,61 +1,183 @@
 use anyhow::Result;
-use jsonschema::{Draft, JSONSchema};
 use modes::{
-    list_jobs::list_jobs_mode, list_tasks::list_tasks_mode, run_job::run_job_mode,
-    run_task::run_task_mode, validate::validate_mode,
+    completions::{complete_names_mode, completions_mode},
+    init::init_mode,
+    list_jobs::list_jobs_mode,
+    list_tasks::list_tasks_mode,
+    run_job::run_job_mode,
+    run_task::run_task_mode,
+    schema::schema_mode,
+    validate::validate_mode,
+    watch::{watch_job_mode, watch_task_mode},
+    ExecutionOptions,
 };
-use std::sync::OnceLock;
-use util::detect_project_file;
+use util::{cli_hook_interrupt, cli_interrupt, detect_project_file, reset_interrupt};
 
-use cli::{CliParameters, Mode};
+use cli::{CliParameters, Mode, OutputFormat};
 
+pub mod api;
+mod builtin;
 pub mod cli;
+pub mod events;
+mod file_watcher;
+mod junit;
 mod modes;
+mod output_buffer;
+pub mod prefix;
+pub mod report;
+pub mod reporter;
 mod schema;
+mod task_cache;
 mod task_executor;
-pub mod util;
+mod trace;
+mod util;
 
-pub static JSONSCHEMA: OnceLock<JSONSchema> = OnceLock::new();
+pub use api::{JobOptions, LoadedProject};
+pub use schema::{
+    builtin::Builtin,
+    condition::Condition,
+    job::Job,
+    matrix::{Matrix, MatrixCombination},
+    output_mode::OutputMode,
+    project::Project,
+    service::{Readiness, ReadinessProbe},
+    settings::Settings,
+    task::Task,
+    task_call::{ForEach, TaskCall},
+    validation::{Diagnostic, DiagnosticSeverity},
+    wait_for::{WaitCondition, WaitFor},
+};
+pub use util::{register_interrupt, terminate_services, Console, MessageSeverity, Verbosity};
 
 pub fn run(args: CliParameters) -> Result<()> {
-    load_jsonschema();
+    let output_args = args.output_args();
+    let console = args.console();
+    // An interrupt of a previous run does not stop this one
+    reset_interrupt();
 
-    let project_file_path = match args.projectfile {
-        Some(path) => path,
-        None => detect_project_file()?,
-    };
+    let projectfile = args.projectfile;
+    let silent_children = args.silent_children.unwrap_or(false);
+    let output = args.output.unwrap_or(OutputFormat::Text);
 
     // Execute the selected mode
     match args.mode {
-        Mode::Validate => validate_mode(project_file_path),
-        Mode::ListTasks => list_tasks_mode(project_file_path),
-        Mode::ListJobs => list_jobs_mode(project_file_path),
+        Mode::Validate => validate_mode(
+            resolve_project_file(projectfile, console)?,
+            output,
+            console,
+        ),
+        Mode::ListTasks {
+            tag,
+            all,
+        } => list_tasks_mode(
+            resolve_project_file(projectfile, console)?,
+            tag,
+            all,
+            output,
+            console,
+        ),
+        Mode::ListJobs {
+            tag,
+        } => list_jobs_mode(
+            resolve_project_file(projectfile, console)?,
+            tag,
+            output,
+            console,
+        ),
         Mode::Run {
             job,
-        } => run_job_mode(project_file_path, job, args.silent_children.unwrap_or(false)),
+            dry_run,
+            force,
+            trace,
+            junit,
+            events,
+            output_mode,
+        } => run_job_mode(
+            resolve_project_file(projectfile, console)?,
+            job,
+            ExecutionOptions {
+                silent_children,
+                dry_run,
+                force,
+                output,
+                trace,
+                junit,
+                events,
+                output_mode,
+                console,
+                interrupt: cli_interrupt(),
+                hook_interrupt: cli_hook_interrupt(),
+            },
+        ),
         Mode::RunTask {
             task,
             arguments,
+            watch: true,
+            clear,
+            ..
+        } => watch_task_mode(
+            resolve_project_file(projectfile, console)?,
+            task,
+            arguments,
+            output_args,
+            clear,
+            console,
+        ),
+        Mode::RunTask {
+            task,
+            arguments,
+            dry_run,
+            force,
+            ..
         } => run_task_mode(
-            project_file_path,
+            resolve_project_file(projectfile, console)?,
             task,
             arguments,
-            args.silent_children.unwrap_or(false),
+            ExecutionOptions {
+                silent_children,
+                dry_run,
+                force,
+                output,
+                trace: None,
+                junit: None,
+                events: None,
+                output_mode: None,
+                console,
+                interrupt: cli_interrupt(),
+                hook_interrupt: cli_hook_interrupt(),
+            },
+        ),
+        Mode::Watch {
+            job,
+            clear,
+        } => watch_job_mode(
+            resolve_project_file(projectfile, console)?,
+            job,
+            output_args,
+            clear,
+            console,
         ),
+        Mode::Init {
+            format,
+            detect,
+            force,
+        } => init_mode(projectfile, format, detect, force, console),
+        Mode::Completions {
+            shell,
+        } => completions_mode(shell),
+        Mode::CompleteNames {
+            kind,
+        } => complete_names_mode(resolve_project_file(projectfile, console)?, kind),
+        Mode::Schema {
+            file,
+        } => schema_mode(file, console),
     }
 }
 
-/// Loads the JSON schema from the jsonschema.json file
-fn load_jsonschema() {
-    // Setup JSONSCHEMA
-    let schema = include_str!("../jsonschema.json");
-    let schema = serde_json::from_str(schema).expect("Error parsing JSON schema");
-    let schema = JSONSchema::options()
-        .with_draft(Draft::Draft7)
-        .compile(&schema);
-
-    let _ = match schema {
-        Ok(final_schema) => JSONSCHEMA.set(final_schema),
-        Err(validation_error) => panic!("Error compiling JSON schema: {}", validation_error),
-    };
+/// Returns the given project file path or tries to auto detect one
+fn resolve_project_file(projectfile: Option<String>, console: Console) -> Result<String> {
+    match projectfile {
+        Some(path) => Ok(path),
+        None => detect_project_file(console),
+    }
 }
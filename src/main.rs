@@ -5,13 +5,12 @@ use clap::Parser;
 
 use setup::cli::CliParameters;
 use setup::run;
-use setup::util::format_error;
-
-pub mod cli;
+use setup::{register_interrupt, terminate_services};
 
 fn main() -> ExitCode {
     // Parse CLI arguments
     let args = CliParameters::parse();
+    let console = args.console();
 
     // Timestamp of the systemtime when the last interrupt was received.
     let mut interrupt_timestamp: Option<SystemTime> = None;
@@ -21,19 +20,21 @@ fn main() -> ExitCode {
         // Check if the last interrupt was received within the last 2 seconds
         if let Some(last_interrupt) = interrupt_timestamp {
             if last_interrupt.elapsed().unwrap().as_secs() < 2 {
-                // If so, exit the program
+                // If so, stop the running services and exit the program
+                terminate_services();
                 std::process::exit(1);
             }
         }
 
         // Update the last interrupt timestamp
         interrupt_timestamp.replace(SystemTime::now());
+        register_interrupt();
     });
 
     match run(args) {
         Ok(_) => ExitCode::SUCCESS,
         Err(error) => {
-            eprintln!("{}", format_error(format!("{}", error)));
+            eprintln!("{}", console.format_error(format!("{}", error)));
             ExitCode::FAILURE
         }
     }
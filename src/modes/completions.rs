@@ -0,0 +1,130 @@
+use anyhow::Result;
+use clap::CommandFactory;
+use clap_complete::{generate, Shell};
+
+use crate::{
+    cli::{CliParameters, CompletionShell, NameKind},
+    schema::project::Project,
+    util::import_project_value,
+};
+
+/// Name of the executable the completion scripts are generated for
+const BIN_NAME: &str = "setup";
+
+/// Executes completions mode. Prints a completion script for the given shell.
+pub fn completions_mode(shell: CompletionShell) -> Result<()> {
+    let mut script: Vec<u8> = Vec::new();
+    let mut command = CliParameters::command();
+    let generator = match shell {
+        CompletionShell::Bash => Shell::Bash,
+        CompletionShell::Zsh => Shell::Zsh,
+        CompletionShell::Fish => Shell::Fish,
+    };
+    generate(generator, &mut command, BIN_NAME, &mut script);
+    let script = String::from_utf8(script)?;
+
+    // Extend the generated script with dynamic completion of job and task names
+    let script = match shell {
+        CompletionShell::Bash => {
+            script.replace("complete -F _setup ", "complete -F _setup_dynamic ")
+                + BASH_DYNAMIC_COMPLETION
+        }
+        CompletionShell::Zsh => {
+            let mut lines: Vec<String> = Vec::new();
+            for line in script.lines() {
+                // The helper functions must be defined before `_setup` is called at the end of the script
+                if line.starts_with("if [ \"$funcstack[1]\" = \"_setup\" ]") {
+                    lines.push(ZSH_DYNAMIC_COMPLETION.trim_start().to_string());
+                }
+
+                if line.starts_with("':job -- ") {
+                    lines.push(line.replace(":_default'", ":_setup_job_names'"));
+                } else if line.starts_with("':task -- ") {
+                    lines.push(line.replace(":_default'", ":_setup_task_names'"));
+                } else {
+                    lines.push(line.to_string());
+                }
+            }
+            format!("{}\n", lines.join("\n"))
+        }
+        CompletionShell::Fish => script + FISH_DYNAMIC_COMPLETION,
+    };
+
+    print!("{}", script);
+    Ok(())
+}
+
+/// Executes complete names mode. Prints the names of all jobs or tasks of the project, one per line.
+pub fn complete_names_mode(projectfile: String, kind: NameKind) -> Result<()> {
+    let project_data = import_project_value(&projectfile)?;
+    let project = Project::import_project(project_data)?;
+
+    let mut names: Vec<&String> = match kind {
+        NameKind::Jobs => project.jobs.keys().collect(),
+        NameKind::Tasks => project
+            .tasks
+            .iter()
+            .filter(|(_, task)| task.hidden != Some(true))
+            .map(|(taskname, _)| taskname)
+            .collect(),
+    };
+    names.sort();
+
+    for name in names {
+        println!("{}", name);
+    }
+    Ok(())
+}
+
+/// Completes the first positional argument of `run`, `watch` and `run-task` with the names from the project file.
+/// The values of options are skipped and an explicit project file is passed on to `complete-names`.
+const BASH_DYNAMIC_COMPLETION: &str = r#"
+_setup_dynamic() {
+    local cur="${COMP_WORDS[COMP_CWORD]}"
+    local i word kind="" projectfile="" positionals=0
+    for (( i = 1; i < COMP_CWORD; i++ )); do
+        word="${COMP_WORDS[i]}"
+        case "${word}" in
+            -s|--silent-children|-o|--output|--color|--trace|--junit|--events|--output-mode) i=$(( i + 1 )) ;;
+            -*) ;;
+            run|watch) [[ -z "${kind}" ]] && kind="jobs" ;;
+            run-task) [[ -z "${kind}" ]] && kind="tasks" ;;
+            *)
+                if [[ -n "${kind}" ]]; then
+                    positionals=$(( positionals + 1 ))
+                elif [[ -z "${projectfile}" ]]; then
+                    projectfile="${word/#\~/${HOME}}"
+                fi
+                ;;
+        esac
+    done
+
+    if [[ -n "${kind}" && ${positionals} -eq 0 && "${cur}" != -* ]]; then
+        COMPREPLY=( $(compgen -W "$(setup ${projectfile:+"${projectfile}"} complete-names ${kind} 2>/dev/null)" -- "${cur}") )
+        return 0
+    fi
+
+    _setup "$@"
+}
+"#;
+
+/// Completes job and task names with the names from the project file
+const ZSH_DYNAMIC_COMPLETION: &str = r#"
+_setup_job_names() {
+    local -a names
+    names=(${(f)"$(setup complete-names jobs 2>/dev/null)"})
+    _describe 'job' names
+}
+
+_setup_task_names() {
+    local -a names
+    names=(${(f)"$(setup complete-names tasks 2>/dev/null)"})
+    _describe 'task' names
+}
+"#;
+
+/// Completes job and task names with the names from the project file
+const FISH_DYNAMIC_COMPLETION: &str = r#"
+complete -c setup -n "__fish_seen_subcommand_from run watch" -f -a "(setup complete-names jobs 2>/dev/null)"
+complete -c setup -n "__fish_seen_subcommand_from run-task" -f -a "(setup complete-names tasks 2>/dev/null)"
+"#;
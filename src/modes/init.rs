@@ -0,0 +1,193 @@
+use std::{collections::HashMap, path::Path};
+
+use anyhow::{anyhow, Result};
+
+use crate::{
+    cli::ProjectFormat,
+    schema::{job::Job, project::Project, task::Task, task_call::TaskCall},
+    util::{Console, MessageSeverity},
+};
+
+/// Name of the job created by init mode
+const DEFAULT_JOB_NAME: &str = "default";
+
+/// Executes init mode. Writes a starter project file and optionally adds tasks for detected ecosystems.
+pub fn init_mode(
+    projectfile: Option<String>,
+    format: ProjectFormat,
+    detect: bool,
+    force: bool,
+    console: Console,
+) -> Result<()> {
+    let projectfile = projectfile.unwrap_or_else(|| match format {
+        ProjectFormat::Json => String::from("Setup.json"),
+        ProjectFormat::Yaml => String::from("Setup.yaml"),
+    });
+
+    if Path::new(&projectfile).exists() && !force {
+        return Err(anyhow!(format!(
+            "Project file \"{}\" already exists. Use --force to overwrite it",
+            projectfile
+        )));
+    }
+
+    // Collect tasks for the project
+    let project_dir = match Path::new(&projectfile).parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => Path::new(".").to_path_buf(),
+    };
+    let mut starter_tasks: Vec<(String, Task)> = Vec::new();
+    if detect {
+        for ecosystem in ECOSYSTEMS {
+            if project_dir.join(ecosystem.marker_file).exists() {
+                console.print_message(
+                    MessageSeverity::Info,
+                    format!("Detected \"{}\", adding tasks", ecosystem.marker_file),
+                );
+                starter_tasks.extend(ecosystem.tasks());
+            }
+        }
+    }
+    if starter_tasks.is_empty() {
+        starter_tasks.push((
+            String::from("hello"),
+            starter_task("echo", &["Hello from setup"]),
+        ));
+    }
+
+    let project = build_project(starter_tasks);
+
+    // Validate and write project
+    let project_value = serde_json::to_value(&project)?;
+    Project::validate_project(&project_value)?;
+    project.check_semantics(console)?;
+
+    let content = match format {
+        ProjectFormat::Json => format!("{}\n", serde_json::to_string_pretty(&project_value)?),
+        ProjectFormat::Yaml => serde_yaml::to_string(&project_value)?,
+    };
+    if let Err(error) = std::fs::write(&projectfile, content) {
+        return Err(anyhow!(format!(
+            "Cannot write file '{projectfile}' ({error})"
+        )));
+    }
+
+    console.print_message(
+        MessageSeverity::Success,
+        format!("Created project file \"{}\"", projectfile),
+    );
+
+    Ok(())
+}
+
+/// Builds a project containing the given tasks and a default job calling all of them sequentially
+fn build_project(starter_tasks: Vec<(String, Task)>) -> Project {
+    let default_job = Job {
+        description: Some(String::from("Runs all tasks of the project")),
+        tags: None,
+        tasks: starter_tasks
+            .iter()
+            .map(|(taskname, _)| TaskCall {
+                task: taskname.clone(),
+                label: None,
+                args: None,
+                when: None,
+                matrix: None,
+                for_each: None,
+                service: None,
+                ready: None,
+            })
+            .collect(),
+        parallel: None,
+        output_mode: None,
+        watch: None,
+        before: None,
+        after: None,
+        on_success: None,
+        on_failure: None,
+        finally: None,
+    };
+
+    Project {
+        settings: None,
+        jobs: HashMap::from([(String::from(DEFAULT_JOB_NAME), default_job)]),
+        tasks: starter_tasks.into_iter().collect(),
+    }
+}
+
+/// Creates a `Task` without call arguments
+fn starter_task(command: &str, args: &[&str]) -> Task {
+    Task {
+        description: None,
+        tags: None,
+        label: None,
+        hidden: None,
+        command: Some(command.to_string()),
+        wait_for: None,
+        builtin: None,
+        args: if args.is_empty() {
+            None
+        } else {
+            Some(args.iter().map(|arg| arg.to_string()).collect())
+        },
+        required_call_args: None,
+        watch: None,
+        inputs: None,
+        outputs: None,
+    }
+}
+
+/// Represents an ecosystem which can be detected by a marker file in the project directory
+struct Ecosystem {
+    marker_file: &'static str,
+    task_prefix: &'static str,
+    commands: &'static [(&'static str, &'static str, &'static [&'static str])],
+}
+
+impl Ecosystem {
+    /// Returns the starter tasks of the ecosystem
+    fn tasks(&self) -> Vec<(String, Task)> {
+        self.commands
+            .iter()
+            .map(|(name, command, args)| {
+                (
+                    format!("{}-{}", self.task_prefix, name),
+                    starter_task(command, args),
+                )
+            })
+            .collect()
+    }
+}
+
+/// All ecosystems detected by init mode
+const ECOSYSTEMS: [Ecosystem; 4] = [
+    Ecosystem {
+        marker_file: "Cargo.toml",
+        task_prefix: "cargo",
+        commands: &[
+            ("build", "cargo", &["build"]),
+            ("test", "cargo", &["test"]),
+        ],
+    },
+    Ecosystem {
+        marker_file: "package.json",
+        task_prefix: "npm",
+        commands: &[
+            ("install", "npm", &["install"]),
+            ("test", "npm", &["test"]),
+        ],
+    },
+    Ecosystem {
+        marker_file: "Makefile",
+        task_prefix: "make",
+        commands: &[("build", "make", &[])],
+    },
+    Ecosystem {
+        marker_file: "pyproject.toml",
+        task_prefix: "python",
+        commands: &[
+            ("install", "pip", &["install", "-e", "."]),
+            ("test", "python", &["-m", "pytest"]),
+        ],
+    },
+];
@@ -1,24 +1,75 @@
+use std::collections::BTreeMap;
+
 use anyhow::Result;
 
 use crate::{
+    cli::OutputFormat,
+    report::{print_json, JobEntry},
     schema::project::Project,
-    util::{import_project_value, print_message, MessageSeverity},
+    util::{format_table, has_tag, import_project_value, Console, MessageSeverity},
 };
 
 /// Executes list jobs mode
-pub fn list_jobs_mode(projectfile: String) -> Result<()> {
+pub fn list_jobs_mode(
+    projectfile: String,
+    tag: Option<String>,
+    output: OutputFormat,
+    console: Console,
+) -> Result<()> {
     // Import project file
     let project_data = import_project_value(&projectfile)?;
     let project = Project::import_project(project_data)?;
 
+    // Collect jobs sorted by name
+    let jobs: BTreeMap<_, _> = project
+        .jobs
+        .iter()
+        .filter(|(_, job)| has_tag(&job.tags, &tag))
+        .collect();
+
+    let entries: Vec<JobEntry> = jobs
+        .iter()
+        .map(|(jobname, job)| {
+            let mut tasknames: Vec<String> = Vec::new();
+            for taskcall in &job.tasks {
+                if !tasknames.contains(&taskcall.task) {
+                    tasknames.push(taskcall.task.clone());
+                }
+            }
+
+            JobEntry {
+                name: jobname.to_string(),
+                description: job.description.clone(),
+                tasks: tasknames,
+                tags: job.tags.clone().unwrap_or_default(),
+                parallel: job.parallel == Some(true),
+            }
+        })
+        .collect();
+
+    if output == OutputFormat::Json {
+        return print_json(&entries);
+    }
+
     // List jobs
-    print_message(
-        MessageSeverity::Info,
+    console.print_message(
+        MessageSeverity::Result,
         format!("Available jobs in project file \"{}\"", projectfile),
     );
 
-    for job in project.jobs.keys() {
-        print_message(MessageSeverity::Info, format!("  - \"{}\"", job));
+    let rows: Vec<Vec<String>> = entries
+        .into_iter()
+        .map(|entry| {
+            vec![
+                entry.name,
+                entry.description.unwrap_or_default(),
+                entry.tasks.join(", "),
+                entry.tags.join(", "),
+            ]
+        })
+        .collect();
+    for line in format_table(&["NAME", "DESCRIPTION", "TASKS", "TAGS"], &rows) {
+        console.print_message(MessageSeverity::Result, format!("  {}", line));
     }
 
     Ok(())
@@ -1,23 +1,79 @@
+use std::collections::BTreeMap;
+
 use anyhow::Result;
 
 use crate::{
+    cli::OutputFormat,
+    report::{print_json, TaskEntry},
     schema::project::Project,
-    util::{import_project_value, print_message, MessageSeverity},
+    util::{format_table, has_tag, import_project_value, Console, MessageSeverity},
 };
 
 /// Executes list tasks mode
-pub fn list_tasks_mode(projectfile: String) -> Result<()> {
+pub fn list_tasks_mode(
+    projectfile: String,
+    tag: Option<String>,
+    all: bool,
+    output: OutputFormat,
+    console: Console,
+) -> Result<()> {
     // Import project file
     let project_data = import_project_value(&projectfile)?;
     let project = Project::import_project(project_data)?;
 
+    // Collect tasks sorted by name
+    let tasks: BTreeMap<_, _> = project
+        .tasks
+        .iter()
+        .filter(|(_, task)| all || task.hidden != Some(true))
+        .filter(|(_, task)| has_tag(&task.tags, &tag))
+        .collect();
+
+    let entries: Vec<TaskEntry> = tasks
+        .iter()
+        .map(|(taskname, task)| TaskEntry {
+            name: taskname.to_string(),
+            description: task.description.clone(),
+            command: task.display_command(),
+            args: task.args.clone().unwrap_or_default(),
+            required_call_args: task.required_call_args.unwrap_or(0),
+            tags: task.tags.clone().unwrap_or_default(),
+            hidden: task.hidden == Some(true),
+        })
+        .collect();
+
+    if output == OutputFormat::Json {
+        return print_json(&entries);
+    }
+
     // List tasks
-    print_message(
-        MessageSeverity::Info,
+    console.print_message(
+        MessageSeverity::Result,
         format!("Available tasks in project file \"{}\"", projectfile),
     );
-    for task in project.tasks.keys() {
-        print_message(MessageSeverity::Info, format!("  - \"{}\"", task));
+
+    let rows: Vec<Vec<String>> = entries
+        .into_iter()
+        .map(|entry| {
+            let mut command = entry.command;
+            for arg in &entry.args {
+                command.push_str(&format!(" {}", arg));
+            }
+
+            vec![
+                entry.name,
+                entry.description.unwrap_or_default(),
+                command,
+                entry.required_call_args.to_string(),
+                entry.tags.join(", "),
+            ]
+        })
+        .collect();
+    for line in format_table(
+        &["NAME", "DESCRIPTION", "COMMAND", "REQUIRED ARGS", "TAGS"],
+        &rows,
+    ) {
+        console.print_message(MessageSeverity::Result, format!("  {}", line));
     }
 
     Ok(())
@@ -1,5 +1,78 @@
+use std::sync::{atomic::AtomicBool, Arc};
+
+use anyhow::{Error, Result};
+
+use crate::{
+    cli::OutputFormat,
+    events::EventStreamReporter,
+    prefix::PrefixTemplate,
+    report::{print_json, ErrorReport},
+    reporter::{ConsoleReporter, MultiReporter, Reporter},
+    schema::{output_mode::OutputMode, settings::Settings},
+    util::Console,
+};
+
+pub mod completions;
+pub mod init;
 pub mod list_jobs;
 pub mod list_tasks;
 pub mod run_job;
 pub mod run_task;
+pub mod schema;
 pub mod validate;
+pub mod watch;
+
+/// Options of the modes which execute tasks
+pub struct ExecutionOptions {
+    pub silent_children: bool,
+    pub dry_run: bool,
+    pub force: bool,
+    pub output: OutputFormat,
+    pub trace: Option<String>,
+    pub junit: Option<String>,
+    pub events: Option<String>,
+    /// Overrides the output mode of the job
+    pub output_mode: Option<OutputMode>,
+    pub console: Console,
+    /// Stops the execution like Ctrl+C once set
+    pub interrupt: Arc<AtomicBool>,
+    /// Stops the steps of the hooks once set, which still run after `interrupt` was set
+    pub hook_interrupt: Arc<AtomicBool>,
+}
+
+impl ExecutionOptions {
+    /// Prints an error which occurred before any task was started as json object with `--output json`,
+    /// so the json output is complete even without a report
+    pub fn report_start_error(&self, error: Error) -> Error {
+        if self.output == OutputFormat::Json {
+            let _ = print_json(&ErrorReport {
+                success: false,
+                error: error.to_string(),
+            });
+        }
+        error
+    }
+
+    /// Returns the reporter of the command line, the console output combined with the requested event stream
+    pub fn reporter(&self, settings: &Option<Settings>) -> Result<Arc<dyn Reporter>> {
+        let prefix = match settings
+            .as_ref()
+            .and_then(|settings| settings.output_prefix.as_ref())
+        {
+            Some(template) => PrefixTemplate::parse(template)?,
+            None => PrefixTemplate::default(),
+        };
+
+        let mut reporter = MultiReporter::default();
+        reporter.push(Arc::new(ConsoleReporter::new(
+            self.console,
+            self.silent_children,
+            self.output == OutputFormat::Text,
+            prefix,
+        )));
+        if let Some(events_target) = &self.events {
+            reporter.push(Arc::new(EventStreamReporter::open(events_target)?));
+        }
+        Ok(Arc::new(reporter))
+    }
+}
@@ -1,30 +1,172 @@
+use std::{
+    cmp::Reverse,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant, SystemTime},
+};
+
 use anyhow::{anyhow, Error, Result};
 
 use crate::{
-    schema::{job::Job, project::Project},
+    cli::OutputFormat,
+    junit::write_junit,
+    prefix::PrefixTemplate,
+    report::{print_json, unix_secs, HookPlan, HookReport, JobPlan, JobReport},
+    reporter::{ConsoleReporter, MessageLevel, Reporter, TaskInfo},
+    schema::{
+        job::{Job, JobHookKind},
+        matrix::{matrix_label, substitute_matrix_values},
+        project::Project,
+        task::Task,
+        task_call::TaskCall,
+    },
+    task_cache::TaskCache,
     task_executor::TaskExecutor,
-    util::{format_error, import_project_value, print_message, MessageSeverity},
+    trace::write_trace,
+    util::{expand_globs, import_project_value, project_dir, Console, MessageSeverity},
 };
 
+use super::ExecutionOptions;
+
 /// Executes the `run` mode
-pub fn run_job_mode(projectfile: String, jobname: String, silent_children: bool) -> Result<()> {
-    let job_execution = JobExecution::new(projectfile, jobname, silent_children)?;
-    job_execution.execute()
+pub fn run_job_mode(projectfile: String, jobname: String, options: ExecutionOptions) -> Result<()> {
+    let mut job_execution = prepare_job_execution(&projectfile, &jobname, &options)
+        .map_err(|error| options.report_start_error(error))?;
+
+    if options.dry_run {
+        job_execution.expand_for_plan()?;
+        return match options.output {
+            OutputFormat::Text => {
+                job_execution.print_plan(options.console);
+                Ok(())
+            }
+            OutputFormat::Json => print_json(&job_execution.plan()),
+        };
+    }
+
+    let report = job_execution.execute(&options)?;
+    if options.output == OutputFormat::Json {
+        print_json(&report)?;
+    }
+
+    // Handle execution result
+    match &report.error {
+        None => {
+            options.console.print_message(
+                MessageSeverity::Success,
+                format!("Job \"{}\" executed successfully", &jobname),
+            );
+            Ok(())
+        }
+        Some(error) => Err(JobExecution::execution_error_handler(
+            &anyhow!(error.clone()),
+            &jobname,
+            options.console,
+        )),
+    }
+}
+
+/// Imports the project and resolves the job. A dry run only reports messages like warnings to the console.
+fn prepare_job_execution(
+    projectfile: &str,
+    jobname: &str,
+    options: &ExecutionOptions,
+) -> Result<JobExecution> {
+    let project_data = import_project_value(projectfile)?;
+    let project = Project::import_project(project_data)?;
+    project.check_semantics(options.console)?;
+
+    let reporter: Arc<dyn Reporter> = if options.dry_run {
+        Arc::new(ConsoleReporter::new(
+            options.console,
+            options.silent_children,
+            false,
+            PrefixTemplate::default(),
+        ))
+    } else {
+        options.reporter(&project.settings)?
+    };
+    JobExecution::new(
+        &project,
+        projectfile,
+        String::from(jobname),
+        options,
+        reporter,
+    )
+}
+
+/// Returns the execution plan of a job of an already imported project
+pub fn plan_job(
+    project: &Project,
+    projectfile: &str,
+    jobname: &str,
+    options: &ExecutionOptions,
+    reporter: Arc<dyn Reporter>,
+) -> Result<JobPlan> {
+    let mut job_execution = JobExecution::new(
+        project,
+        projectfile,
+        String::from(jobname),
+        options,
+        reporter,
+    )?;
+    job_execution.expand_for_plan()?;
+    Ok(job_execution.plan())
+}
+
+/// Executes a job of an already imported project and returns its report
+pub fn execute_job(
+    project: &Project,
+    projectfile: &str,
+    jobname: &str,
+    options: &ExecutionOptions,
+    reporter: Arc<dyn Reporter>,
+) -> Result<JobReport> {
+    JobExecution::new(
+        project,
+        projectfile,
+        String::from(jobname),
+        options,
+        reporter,
+    )?
+    .execute(options)
 }
 
 struct JobExecution {
     jobname: String,
     job: Job,
+    before_executors: Vec<TaskExecutor>,
+    task_executors: Vec<TaskExecutor>,
+    after_executors: Vec<TaskExecutor>,
+    hooks: Vec<JobHook>,
+    /// Directory the `for_each` globs are relative to
+    project_dir: PathBuf,
+    reporter: Arc<dyn Reporter>,
+    /// Stops the job like Ctrl+C once set
+    interrupt: Arc<AtomicBool>,
+    /// Stops the steps of the hooks once set
+    hook_interrupt: Arc<AtomicBool>,
+}
+
+/// Steps of a single job hook
+struct JobHook {
+    kind: JobHookKind,
     task_executors: Vec<TaskExecutor>,
+    success: Option<bool>,
 }
 
 impl JobExecution {
     /// Creates a new `JobExecution` instance
-    fn new(projectfile: String, jobname: String, silent_children: bool) -> Result<JobExecution> {
-        // Import project file
-        let project_data = import_project_value(&projectfile)?;
-        let project = Project::import_project(project_data)?;
-
+    fn new(
+        project: &Project,
+        projectfile: &str,
+        jobname: String,
+        options: &ExecutionOptions,
+        reporter: Arc<dyn Reporter>,
+    ) -> Result<JobExecution> {
         // Get the tasknames associated with the job
         let Some(job) = project.jobs.get(&jobname) else {
             return Err(anyhow!(format!("Job with name \"{}\" not found", &jobname)));
@@ -35,7 +177,7 @@ impl JobExecution {
         let work_dir: Option<String> = {
             if let Some(settings) = &project.settings {
                 match settings.project_file_as_work_dir {
-                    Some(true) => Some(projectfile.clone()),
+                    Some(true) => Some(String::from(projectfile)),
                     _ => None,
                 }
             } else {
@@ -43,135 +185,630 @@ impl JobExecution {
             }
         };
 
+        let project_dir = project_dir(projectfile)?;
+        let cache = Arc::new(Mutex::new(TaskCache::load(&project_dir, options.force)));
+        let output_mode = options.output_mode.or(job.output_mode).unwrap_or_default();
+
         // Build `TaskExecutor` instances for each task
-        let mut task_executors: Vec<TaskExecutor> = Vec::new();
-        for taskcall in job.tasks.iter() {
-            let Some(task) = project.tasks.get(&taskcall.task) else {
-                return Err(Self::execution_error_handler(
-                    &anyhow!(format!("Task with name \"{}\" not found", taskcall.task)),
-                    &jobname,
-                ));
+        let build_executors =
+            |taskcalls: &[TaskCall], interrupt: &Arc<AtomicBool>| -> Result<Vec<TaskExecutor>> {
+                let mut task_executors: Vec<TaskExecutor> = Vec::new();
+                for taskcall in taskcalls {
+                    let Some(task) = project.tasks.get(&taskcall.task) else {
+                        return Err(anyhow!(format!(
+                            "Task with name \"{}\" not found",
+                            taskcall.task
+                        )));
+                    };
+
+                    task_executors.extend(Self::build_step_executors(
+                        task, taskcall, &work_dir, &reporter, &cache,
+                    )?);
+                }
+                for executor in &mut task_executors {
+                    executor.output_mode = output_mode;
+                    executor.interrupt = interrupt.clone();
+                }
+                Ok(task_executors)
             };
 
-            task_executors.push(TaskExecutor::new(
-                task,
-                taskcall,
-                &work_dir,
-                silent_children,
-            )?);
+        let interrupt = &options.interrupt;
+        let before_executors =
+            build_executors(job.before.as_deref().unwrap_or_default(), interrupt)?;
+        let task_executors = build_executors(&job.tasks, interrupt)?;
+        let after_executors = build_executors(job.after.as_deref().unwrap_or_default(), interrupt)?;
+        let mut hooks: Vec<JobHook> = Vec::new();
+        for (kind, taskcalls) in job.hooks() {
+            hooks.push(JobHook {
+                kind,
+                // Hooks still run after an interrupt, so they get their own flag
+                task_executors: build_executors(
+                    taskcalls.as_deref().unwrap_or_default(),
+                    &options.hook_interrupt,
+                )?,
+                success: None,
+            });
         }
+        reporter.message(
+            MessageLevel::Verbose,
+            &format!(
+                "Job \"{}\" resolved into {} before, {} main and {} after steps with output mode {}",
+                jobname,
+                before_executors.len(),
+                task_executors.len(),
+                after_executors.len(),
+                output_mode
+            ),
+        );
 
         Ok(JobExecution {
             jobname,
             job,
+            before_executors,
             task_executors,
+            after_executors,
+            hooks,
+            project_dir,
+            reporter,
+            interrupt: options.interrupt.clone(),
+            hook_interrupt: options.hook_interrupt.clone(),
         })
     }
 
-    /// Executes the job
-    fn execute(self) -> Result<()> {
-        let jobname = self.jobname.clone();
-        print_message(
-            MessageSeverity::Info,
-            format!("Executing job \"{}\"", &jobname),
+    /// Builds the `TaskExecutor` instances of a single step. Steps with a matrix are expanded into one
+    /// instance per combination. Steps with `for_each` are kept as templates, which are expanded into one
+    /// instance per file or batch of files when the step starts, so files created by earlier steps are included.
+    fn build_step_executors(
+        task: &Task,
+        taskcall: &TaskCall,
+        work_dir: &Option<String>,
+        reporter: &Arc<dyn Reporter>,
+        cache: &Arc<Mutex<TaskCache>>,
+    ) -> Result<Vec<TaskExecutor>> {
+        let instances: Vec<(Task, TaskCall, Option<String>)> = match &taskcall.matrix {
+            Some(matrix) => matrix
+                .combinations()
+                .iter()
+                .map(|combination| {
+                    let substitute_args = |args: &Option<Vec<String>>| {
+                        args.as_ref().map(|args| {
+                            args.iter()
+                                .map(|arg| substitute_matrix_values(arg, combination))
+                                .collect()
+                        })
+                    };
+
+                    let mut expanded_task = task.clone();
+                    expanded_task.args = substitute_args(&task.args);
+                    let mut expanded_taskcall = taskcall.clone();
+                    expanded_taskcall.args = substitute_args(&taskcall.args);
+
+                    (
+                        expanded_task,
+                        expanded_taskcall,
+                        Some(matrix_label(combination)),
+                    )
+                })
+                .collect(),
+            None => vec![(task.clone(), taskcall.clone(), None)],
+        };
+
+        let mut executors: Vec<TaskExecutor> = Vec::new();
+        for (task, taskcall, label) in instances {
+            let mut executor = TaskExecutor::new(&task, &taskcall, work_dir, reporter, cache)?;
+            if let Some(label) = label {
+                executor.append_label(label);
+            }
+            executors.push(executor);
+        }
+
+        Ok(executors)
+    }
+
+    /// Expands the template of a `for_each` step into one executor per file or batch of files matching
+    /// the glob at this moment
+    fn expand_for_each(
+        template: &TaskExecutor,
+        project_dir: &Path,
+        reporter: &Arc<dyn Reporter>,
+    ) -> Result<Vec<TaskExecutor>> {
+        let Some(for_each) = &template.taskcall.for_each else {
+            return Ok(Vec::new());
+        };
+
+        let files = expand_globs(project_dir, std::slice::from_ref(&for_each.glob))?;
+        if files.is_empty() {
+            reporter.message(
+                MessageLevel::Warning,
+                &format!(
+                    "No files match \"{}\" of task \"{}\"",
+                    for_each.glob, template.taskname
+                ),
+            );
+        }
+
+        // Pass files relative to the working directory if possible
+        let work_dir = template.work_dir();
+        let work_dir = work_dir.canonicalize().unwrap_or(work_dir);
+        let file_args: Vec<String> = files
+            .iter()
+            .map(|file| {
+                file.strip_prefix(&work_dir)
+                    .unwrap_or(file)
+                    .display()
+                    .to_string()
+            })
+            .collect();
+
+        let batch_size = for_each.batch_size.unwrap_or(1) as usize;
+        let executors = file_args
+            .chunks(batch_size)
+            .map(|batch| template.for_files(batch))
+            .collect::<Result<Vec<TaskExecutor>>>()?;
+        let tasks: Vec<TaskInfo> = executors.iter().map(TaskExecutor::info).collect();
+        reporter.tasks_planned(&tasks);
+        Ok(executors)
+    }
+
+    /// Replaces the templates of `for_each` steps by the executors for the files matching their globs
+    fn expand_all_for_each(
+        executors: &mut Vec<TaskExecutor>,
+        project_dir: &Path,
+        reporter: &Arc<dyn Reporter>,
+    ) -> Result<()> {
+        let mut expanded: Vec<TaskExecutor> = Vec::new();
+        for executor in executors.drain(..) {
+            if executor.is_for_each() {
+                expanded.extend(Self::expand_for_each(&executor, project_dir, reporter)?);
+            } else {
+                expanded.push(executor);
+            }
+        }
+        *executors = expanded;
+        Ok(())
+    }
+
+    /// Expands all `for_each` steps with the files matching their globs now, to plan the job without executing it
+    fn expand_for_plan(&mut self) -> Result<()> {
+        let step_lists = [
+            &mut self.before_executors,
+            &mut self.task_executors,
+            &mut self.after_executors,
+        ]
+        .into_iter()
+        .chain(self.hooks.iter_mut().map(|hook| &mut hook.task_executors));
+        for executors in step_lists {
+            Self::expand_all_for_each(executors, &self.project_dir, &self.reporter)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the execution plan of the job
+    fn plan(&self) -> JobPlan {
+        JobPlan {
+            job: self.jobname.clone(),
+            parallel: self.job.parallel == Some(true),
+            steps: self
+                .main_executors()
+                .map(|executor| executor.plan())
+                .collect(),
+            hooks: self
+                .hooks
+                .iter()
+                .filter(|hook| !hook.task_executors.is_empty())
+                .map(|hook| HookPlan {
+                    hook: String::from(hook.kind.name()),
+                    steps: hook
+                        .task_executors
+                        .iter()
+                        .map(|executor| executor.plan())
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the executors of the `before` steps, the tasks and the `after` steps in execution order
+    fn main_executors(&self) -> impl Iterator<Item = &TaskExecutor> {
+        self.before_executors
+            .iter()
+            .chain(self.task_executors.iter())
+            .chain(self.after_executors.iter())
+    }
+
+    /// Prints the execution plan of the job without executing any task
+    fn print_plan(&self, console: Console) {
+        let print_steps = |name: &str, executors: &Vec<TaskExecutor>| {
+            if executors.is_empty() {
+                return;
+            }
+            console.print_message(
+                MessageSeverity::Result,
+                format!(
+                    "Steps \"{}\" of job \"{}\" would execute {} task(s) sequentially",
+                    name,
+                    self.jobname,
+                    executors.len()
+                ),
+            );
+            for (index, executor) in executors.iter().enumerate() {
+                executor.print_plan(index + 1, console);
+            }
+        };
+
+        // Steps are printed in the order of their execution
+        print_steps("before", &self.before_executors);
+
+        let ordering = match self.job.parallel {
+            Some(true) => "in parallel",
+            _ => "sequentially",
+        };
+        console.print_message(
+            MessageSeverity::Result,
+            format!(
+                "Job \"{}\" would execute {} task(s) {}",
+                self.jobname,
+                self.task_executors.len(),
+                ordering
+            ),
         );
+        for (index, executor) in self.task_executors.iter().enumerate() {
+            executor.print_plan(index + 1, console);
+        }
+
+        print_steps("after", &self.after_executors);
+        for hook in &self.hooks {
+            print_steps(hook.kind.name(), &hook.task_executors);
+        }
+    }
+
+    /// Executes the job. A failing job is reported through the `error` of the report, not as an error.
+    fn execute(mut self, options: &ExecutionOptions) -> Result<JobReport> {
+        let jobname = self.jobname.clone();
 
         // Call executors functions
-        let execution_result = match self.job.parallel {
-            Some(true) => self.execute_parallel(),
-            _ => self.execute_sequential(),
-        };
+        let start_time = Instant::now();
+        let started_at = SystemTime::now();
+        self.reporter.job_started(&jobname);
+        self.reporter.tasks_planned(&self.planned_tasks());
+        let execution_result = self.execute_steps();
 
-        // Handle execution result
-        match execution_result {
-            Ok(()) => {
-                print_message(
-                    MessageSeverity::Success,
-                    format!("Job \"{}\" executed successfully", &jobname),
+        // Execute the hooks matching the outcome of the job, an interrupt counts as a failure
+        for hook in &mut self.hooks {
+            if !hook.task_executors.is_empty() && hook.kind.runs_after(execution_result.is_ok()) {
+                Self::execute_hook(
+                    &jobname,
+                    hook,
+                    &self.hook_interrupt,
+                    &self.project_dir,
+                    &self.reporter,
                 );
-                Ok(())
             }
-            Err(error) => Err(Self::execution_error_handler(&error, &jobname)),
         }
+        self.stop_services();
+
+        let report = self.report(
+            execution_result.err().map(|error| error.to_string()),
+            started_at,
+            start_time.elapsed(),
+        );
+        self.reporter.job_finished(&report);
+
+        // The job already ran, so failing to write a report file does not change its outcome
+        if let Some(trace_file) = &options.trace {
+            if let Err(error) = write_trace(trace_file, &report) {
+                self.reporter
+                    .message(MessageLevel::Warning, &error.to_string());
+            }
+        }
+        if let Some(junit_file) = &options.junit {
+            if let Err(error) = write_junit(junit_file, &report) {
+                self.reporter
+                    .message(MessageLevel::Warning, &error.to_string());
+            }
+        }
+        Ok(report)
+    }
+
+    /// Returns the results of all tasks of the job
+    fn report(
+        &self,
+        error: Option<String>,
+        started_at: SystemTime,
+        duration: Duration,
+    ) -> JobReport {
+        JobReport {
+            job: self.jobname.clone(),
+            parallel: self.job.parallel == Some(true),
+            success: error.is_none(),
+            error,
+            started_at: unix_secs(started_at),
+            finished_at: unix_secs(started_at + duration),
+            duration_secs: duration.as_secs_f64(),
+            tasks: self
+                .main_executors()
+                .map(|executor| executor.report())
+                .collect(),
+            hooks: self
+                .hooks
+                .iter()
+                .filter_map(|hook| {
+                    Some(HookReport {
+                        hook: String::from(hook.kind.name()),
+                        success: hook.success?,
+                        tasks: hook
+                            .task_executors
+                            .iter()
+                            .map(|executor| executor.report())
+                            .collect(),
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    /// Executes the `before` steps, the tasks and the `after` steps of the job
+    fn execute_steps(&mut self) -> Result<()> {
+        let interrupt = Some(self.interrupt.as_ref());
+        let (project_dir, reporter) = (&self.project_dir, &self.reporter);
+        Self::execute_sequential(&mut self.before_executors, interrupt, project_dir, reporter)?;
+        match self.job.parallel {
+            Some(true) => Self::execute_parallel(
+                &mut self.task_executors,
+                &self.interrupt,
+                project_dir,
+                reporter,
+            )?,
+            _ => Self::execute_sequential(
+                &mut self.task_executors,
+                interrupt,
+                project_dir,
+                reporter,
+            )?,
+        }
+        Self::execute_sequential(&mut self.after_executors, interrupt, project_dir, reporter)?;
+
+        if self.interrupt.load(Ordering::Relaxed) {
+            return Err(anyhow!("Execution was interrupted"));
+        }
+        Ok(())
+    }
+
+    /// Returns the tasks of the job and its hooks. The runs of `for_each` steps are planned once their files
+    /// are expanded.
+    fn planned_tasks(&self) -> Vec<TaskInfo> {
+        self.before_executors
+            .iter()
+            .chain(self.task_executors.iter())
+            .chain(self.after_executors.iter())
+            .chain(
+                self.hooks
+                    .iter()
+                    .flat_map(|hook| hook.task_executors.iter()),
+            )
+            .filter(|executor| !executor.is_for_each())
+            .map(TaskExecutor::info)
+            .collect()
+    }
+
+    /// Stops all running services in reverse start order
+    fn stop_services(&mut self) {
+        let mut services: Vec<&mut TaskExecutor> = self
+            .before_executors
+            .iter_mut()
+            .chain(self.task_executors.iter_mut())
+            .chain(self.after_executors.iter_mut())
+            .chain(
+                self.hooks
+                    .iter_mut()
+                    .flat_map(|hook| hook.task_executors.iter_mut()),
+            )
+            .filter(|executor| executor.is_running_service())
+            .collect();
+
+        services.sort_by_key(|executor| Reverse(executor.start_time()));
+        for service in services {
+            service.stop_service();
+        }
+    }
+
+    /// Executes the steps of a hook. Failures are reported separately and do not change the outcome of the job.
+    fn execute_hook(
+        jobname: &str,
+        hook: &mut JobHook,
+        interrupt: &AtomicBool,
+        project_dir: &Path,
+        reporter: &Arc<dyn Reporter>,
+    ) {
+        reporter.hook_started(jobname, hook.kind.name());
+        let hook_result = Self::execute_sequential(
+            &mut hook.task_executors,
+            Some(interrupt),
+            project_dir,
+            reporter,
+        );
+        let error = hook_result.as_ref().err().map(|error| error.to_string());
+        reporter.hook_finished(jobname, hook.kind.name(), error.as_deref());
+        hook.success = Some(hook_result.is_ok());
     }
 
     /// Prints the given error and returns a execution failed error
-    fn execution_error_handler(error: &Error, jobname: &str) -> anyhow::Error {
-        eprintln!("{}", format_error(format!("{}", error)));
+    fn execution_error_handler(error: &Error, jobname: &str, console: Console) -> anyhow::Error {
+        eprintln!("{}", console.format_error(format!("{}", error)));
         anyhow!(format!("Job \"{}\" failed", jobname))
     }
 
-    /// Executes multiple tasks sequentially
-    fn execute_sequential(mut self) -> Result<()> {
+    /// Checks whether the `when` condition of the step is met. Steps without a condition are always executed.
+    fn condition_met(executor: &TaskExecutor) -> bool {
+        match &executor.taskcall.when {
+            Some(condition) => condition.evaluate(&executor.work_dir()),
+            None => true,
+        }
+    }
+
+    /// Executes multiple tasks sequentially. With an interrupt flag, no further task is started once it is set.
+    /// A failed file of a `for_each` step fails the job only after all files of the step were executed.
+    fn execute_sequential(
+        executors: &mut Vec<TaskExecutor>,
+        interrupt: Option<&AtomicBool>,
+        project_dir: &Path,
+        reporter: &Arc<dyn Reporter>,
+    ) -> Result<()> {
+        // Range of the executors of the current `for_each` step and the amount of failed ones
+        let mut for_each_steps = 0..0;
+        let mut for_each_failures = 0;
+
         // Execute each `TaskExecutor` and wait for it to finish
-        for executor in &mut self.task_executors {
+        let mut index = 0;
+        loop {
+            if index == for_each_steps.end && for_each_failures > 0 {
+                return Err(anyhow!(format!(
+                    "{} of {} runs of task \"{}\" failed",
+                    for_each_failures,
+                    for_each_steps.len(),
+                    executors[for_each_steps.start].taskname
+                )));
+            }
+            let Some(executor) = executors.get_mut(index) else {
+                return Ok(());
+            };
+
+            if interrupt.is_some_and(|interrupt| interrupt.load(Ordering::Relaxed)) {
+                return Err(anyhow!("Execution was interrupted"));
+            }
+
+            if !Self::condition_met(executor) {
+                executor.skip("condition not met");
+                index += 1;
+                continue;
+            }
+
+            // Steps with `for_each` are expanded when they start, so files created by earlier steps are included
+            if executor.is_for_each() {
+                let step_executors = Self::expand_for_each(executor, project_dir, reporter)?;
+                for_each_steps = index..index + step_executors.len();
+                executors.splice(index..=index, step_executors);
+                continue;
+            }
             executor.execute()?;
 
+            // Services keep running while the following steps are executed
+            if executor.is_service() {
+                executor.wait_until_ready()?;
+                index += 1;
+                continue;
+            }
+
             if !executor.wait().unwrap().success() {
-                return Err(anyhow!(format!(
-                    "Task \"{}\" failed",
-                    executor.task.command
-                )));
-            } else {
-                print_message(
-                    MessageSeverity::Success,
-                    format!(
-                        "Task \"{}\" executed successfully",
-                        executor.execution_string
-                    ),
-                );
+                let error = format!("Task \"{}\" failed", executor.display_name());
+                if !for_each_steps.contains(&index) {
+                    return Err(anyhow!(error));
+                }
+
+                // The remaining files of the `for_each` step are executed anyway
+                reporter.message(MessageLevel::Error, &error);
+                for_each_failures += 1;
             }
+            index += 1;
         }
-
-        Ok(())
     }
 
     /// Executes multiple tasks in parallel
-    fn execute_parallel(mut self) -> Result<()> {
-        // Store task status
-        let mut task_status: Vec<bool> = vec![false; self.task_executors.len()];
+    fn execute_parallel(
+        executors: &mut Vec<TaskExecutor>,
+        interrupt: &AtomicBool,
+        project_dir: &Path,
+        reporter: &Arc<dyn Reporter>,
+    ) -> Result<()> {
+        // Steps with `for_each` are expanded now, so files created by earlier steps are included.
+        // The executors are only replaced once all expansions succeeded, so no step is lost on an error.
+        let mut expansions: Vec<Option<Vec<TaskExecutor>>> = Vec::new();
+        for executor in executors.iter_mut() {
+            if !executor.is_for_each() {
+                expansions.push(None);
+            } else if Self::condition_met(executor) {
+                let expanded = Self::expand_for_each(executor, project_dir, reporter)?;
+                expansions.push(Some(expanded));
+            } else {
+                executor.skip("condition not met");
+                expansions.push(None);
+            }
+        }
+        let mut step_executors: Vec<TaskExecutor> = Vec::new();
+        for (executor, expansion) in executors.drain(..).zip(expansions) {
+            match expansion {
+                Some(expanded) => step_executors.extend(expanded),
+                None => step_executors.push(executor),
+            }
+        }
+        *executors = step_executors;
+        for (index, executor) in executors.iter_mut().enumerate() {
+            executor.parallel_index = Some(index);
+        }
+
+        // Store task status, services are not waited for
+        let mut task_status: Vec<bool> = executors
+            .iter()
+            .map(|executor| executor.is_service())
+            .collect();
         let mut task_failed = false;
 
-        // Execute each `TaskExecutor` without waiting for it to finish
-        for executor in &mut self.task_executors {
-            executor.execute()?;
+        // Execute each `TaskExecutor` without waiting for it to finish. No task is started after an interrupt
+        // or a task which could not be started, but the already started ones are still waited for.
+        let mut start_error: Option<Error> = None;
+        for (index, executor) in executors.iter_mut().enumerate() {
+            if start_error.is_some() || interrupt.load(Ordering::Relaxed) {
+                task_status[index] = true;
+                continue;
+            }
+            if executor.is_skipped() {
+                continue;
+            }
+            if !Self::condition_met(executor) {
+                executor.skip("condition not met");
+                continue;
+            }
+            let mut started = executor.execute();
+            if started.is_ok() && executor.is_service() {
+                started = executor.wait_until_ready();
+            }
+            if let Err(error) = started {
+                task_status[index] = true;
+                start_error = Some(error);
+            }
         }
 
-        // Wait for all tasks to finish
+        // Wait for all tasks to finish, running tasks are terminated after an interrupt
+        let mut terminated = false;
         loop {
             let mut all_finished = true;
+            if !terminated && interrupt.load(Ordering::Relaxed) {
+                executors
+                    .iter_mut()
+                    .filter(|executor| !executor.is_service())
+                    .for_each(TaskExecutor::terminate);
+                terminated = true;
+            }
 
-            for (index, executor) in self.task_executors.iter_mut().enumerate() {
+            for (index, executor) in executors.iter_mut().enumerate() {
                 if !task_status[index] {
                     all_finished = false;
                     match executor.try_wait() {
                         Ok(statuscode) => {
                             if let Some(statuscode) = statuscode {
                                 task_status[index] = true;
-                                if !statuscode.success() {
+                                if !statuscode.success() && !terminated {
                                     task_failed = true;
-                                    eprintln!(
-                                        "{}",
-                                        format_error(format!(
-                                            "Task \"{}\" failed",
-                                            executor.task.command
-                                        ))
-                                    );
-                                } else {
-                                    print_message(
-                                        MessageSeverity::Success,
-                                        format!(
-                                            "Task \"{}\" executed successfully",
-                                            executor.task.command
-                                        ),
+                                    reporter.message(
+                                        MessageLevel::Error,
+                                        &format!("Task \"{}\" failed", executor.display_name()),
                                     );
                                 }
                             }
                         }
-                        Err(_) => eprintln!(
-                            "{}",
-                            format_error(String::from("Something unexpected happened"))
-                        ),
+                        Err(_) => {
+                            reporter.message(MessageLevel::Error, "Something unexpected happened")
+                        }
                     }
                 }
             }
@@ -181,7 +818,11 @@ impl JobExecution {
             }
         }
 
-        if task_failed {
+        if interrupt.load(Ordering::Relaxed) {
+            Err(anyhow!("Execution was interrupted"))
+        } else if let Some(error) = start_error {
+            Err(error)
+        } else if task_failed {
             let error_message = String::from("Atleast one task failed to finished successfully");
             Err(anyhow!(error_message))
         } else {
@@ -189,3 +830,10 @@ impl JobExecution {
         }
     }
 }
+
+impl Drop for JobExecution {
+    /// Stops services which are still running if the execution is aborted early, e.g. by a panic
+    fn drop(&mut self) {
+        self.stop_services();
+    }
+}
@@ -1,22 +1,44 @@
-use std::process::ExitStatus;
+use std::{
+    process::ExitStatus,
+    sync::{Arc, Mutex},
+};
 
 use anyhow::{anyhow, Result};
 
 use crate::{
+    cli::OutputFormat,
+    report::print_json,
     schema::{project::Project, task_call::TaskCall},
+    task_cache::TaskCache,
     task_executor::TaskExecutor,
-    util::{format_error, import_project_value, print_message, MessageSeverity},
+    util::{import_project_value, project_dir, MessageSeverity},
 };
 
+use super::ExecutionOptions;
+
 /// Executes run task mode
 pub fn run_task_mode(
     projectfile: String,
     task: String,
     arguments: Vec<String>,
-    silent_children: bool,
+    options: ExecutionOptions,
 ) -> Result<()> {
-    let task_execution = TaskExecution::new(projectfile, task, arguments, silent_children)?;
-    task_execution.execute()
+    let task_execution = TaskExecution::new(projectfile, task, arguments, &options)
+        .map_err(|error| options.report_start_error(error))?;
+    if options.dry_run {
+        return match options.output {
+            OutputFormat::Text => {
+                options.console.print_message(
+                    MessageSeverity::Result,
+                    format!("Task \"{}\" would be executed", task_execution.taskname),
+                );
+                task_execution.task_executor.print_plan(1, options.console);
+                Ok(())
+            }
+            OutputFormat::Json => print_json(&task_execution.task_executor.plan()),
+        };
+    }
+    task_execution.execute(&options)
 }
 
 struct TaskExecution {
@@ -29,11 +51,12 @@ impl TaskExecution {
         projectfile: String,
         task: String,
         arguments: Vec<String>,
-        silent_children: bool,
+        options: &ExecutionOptions,
     ) -> Result<TaskExecution> {
         // Import project file
         let project_data = import_project_value(&projectfile)?;
         let project = Project::import_project(project_data)?;
+        project.check_semantics(options.console)?;
 
         // Check working dir
         let work_dir: Option<String> = if let Some(settings) = &project.settings {
@@ -45,12 +68,17 @@ impl TaskExecution {
             None
         };
 
+        let cache = Arc::new(Mutex::new(TaskCache::load(
+            &project_dir(&projectfile)?,
+            options.force,
+        )));
+
         // Build `TaskExecutor` instance
         let task_executor =
-            match build_executor(&project, &task, arguments, work_dir, silent_children) {
+            match build_executor(&project, &task, arguments, work_dir, options, &cache) {
                 Ok(executor) => executor,
                 Err(error) => {
-                    eprintln!("{}", format_error(format!("{}", error)));
+                    eprintln!("{}", options.console.format_error(format!("{}", error)));
                     return Err(anyhow!(format!("Task \"{}\" failed", &task)));
                 }
             };
@@ -61,31 +89,31 @@ impl TaskExecution {
         })
     }
 
-    fn execute(self) -> Result<()> {
+    fn execute(mut self, options: &ExecutionOptions) -> Result<()> {
         // Execute task
-        print_message(
+        options.console.print_message(
             MessageSeverity::Info,
             format!("Executing task with name \"{}\"", self.taskname),
         );
 
         let taskname: String = self.task_executor.execution_string.clone();
-        match self.execute_task() {
-            Ok(()) => {
-                print_message(
-                    MessageSeverity::Success,
-                    format!("Task \"{}\" executed successfully", &taskname),
-                );
-                Ok(())
-            }
+        let execution_result = self.execute_task();
+
+        if options.output == OutputFormat::Json {
+            print_json(&self.task_executor.report())?;
+        }
+
+        match execution_result {
+            Ok(()) => Ok(()),
             Err(error) => {
-                eprintln!("{}", format_error(format!("{}", error)));
+                eprintln!("{}", options.console.format_error(format!("{}", error)));
                 Err(anyhow!(format!("Task \"{}\" failed", &taskname)))
             }
         }
     }
 
     /// Executes the task with the given name
-    fn execute_task(mut self) -> Result<()> {
+    fn execute_task(&mut self) -> Result<()> {
         self.task_executor.execute()?;
 
         let status = self.task_executor.wait()?;
@@ -111,7 +139,8 @@ fn build_executor(
     taskname: &str,
     arguments: Vec<String>,
     work_dir: Option<String>,
-    silent_children: bool,
+    options: &ExecutionOptions,
+    cache: &Arc<Mutex<TaskCache>>,
 ) -> Result<TaskExecutor> {
     // Get the task
     let Some(task) = project.tasks.get(taskname) else {
@@ -142,9 +171,23 @@ fn build_executor(
     // Manually build taskcall
     let taskcall = TaskCall {
         task: taskname.to_string(),
+        label: None,
         args: Some(arguments),
+        when: None,
+        matrix: None,
+        for_each: None,
+        service: None,
+        ready: None,
     };
 
     // Build `TaskExecutor` instance
-    TaskExecutor::new(task, &taskcall, &work_dir, silent_children)
+    let mut task_executor = TaskExecutor::new(
+        task,
+        &taskcall,
+        &work_dir,
+        &options.reporter(&project.settings)?,
+        cache,
+    )?;
+    task_executor.interrupt = options.interrupt.clone();
+    Ok(task_executor)
 }
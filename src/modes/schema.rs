@@ -0,0 +1,38 @@
+use std::io::{ErrorKind, Write};
+
+use anyhow::{anyhow, Result};
+
+use crate::{
+    schema::project::Project,
+    util::{Console, MessageSeverity},
+};
+
+/// Executes schema mode. Prints the json schema of the project file or writes it to the given file.
+pub fn schema_mode(file: Option<String>, console: Console) -> Result<()> {
+    let schema = Project::generate_jsonschema();
+    let schema = serde_json::to_string_pretty(&schema)?;
+
+    match file {
+        Some(file) => {
+            if let Err(error) = std::fs::write(&file, format!("{}\n", schema)) {
+                return Err(anyhow!(format!(
+                    "Cannot write file '{file}' ({error})"
+                )));
+            }
+            console.print_message(
+                MessageSeverity::Success,
+                format!("Json schema written to \"{}\"", file),
+            );
+        }
+        None => {
+            if let Err(error) = writeln!(std::io::stdout().lock(), "{}", schema) {
+                // A closed pipe, e.g. of `setup schema | head`, is no error
+                if error.kind() != ErrorKind::BrokenPipe {
+                    return Err(anyhow!(format!("Cannot print the json schema ({})", error)));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
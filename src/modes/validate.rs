@@ -1,23 +1,58 @@
 use crate::{
-    schema::project::Project,
-    util::{import_project_value, print_message, MessageSeverity},
+    cli::OutputFormat,
+    report::{print_json, ValidationReport},
+    schema::{
+        project::Project,
+        validation::{Diagnostic, DiagnosticSeverity},
+    },
+    util::{import_project_value, Console, MessageSeverity},
 };
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
-/// Executes validate mode. This mode checks whether the project file follows the jsonschema correctly.
+/// Executes validate mode. This mode checks whether the project file follows the jsonschema correctly
+/// and whether its jobs and tasks are consistent with each other.
 /// - `return` - Returns whether the the execution of the mode finished successfully or not.
-pub fn validate_mode(projectfile: String) -> Result<()> {
-    print_message(
+pub fn validate_mode(projectfile: String, output: OutputFormat, console: Console) -> Result<()> {
+    console.print_message(
         MessageSeverity::Info,
         format!("Validating project file \"{}\"", projectfile),
     );
 
+    if output == OutputFormat::Json {
+        let diagnostics = collect_diagnostics(&projectfile);
+        let valid = !diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.severity == DiagnosticSeverity::Error);
+
+        print_json(&ValidationReport {
+            file: projectfile,
+            valid,
+            diagnostics,
+        })?;
+
+        return if valid {
+            Ok(())
+        } else {
+            Err(anyhow!("Project file is invalid"))
+        };
+    }
+
     let project_data = import_project_value(&projectfile)?;
-    Project::validate_project(&project_data)?;
-    print_message(
+    let project = Project::import_project(project_data)?;
+    project.check_semantics(console)?;
+    console.print_message(
         MessageSeverity::Success,
         String::from("Project file is valid"),
     );
 
     Ok(())
 }
+
+/// Collects all findings of the validation. Errors while importing the project are returned as a single finding.
+fn collect_diagnostics(projectfile: &str) -> Vec<Diagnostic> {
+    let project = import_project_value(projectfile).and_then(Project::import_project);
+    match project {
+        Ok(project) => project.semantic_diagnostics(),
+        Err(error) => vec![Diagnostic::error(format!("{}", error))],
+    }
+}
@@ -0,0 +1,234 @@
+use std::{
+    io::Write,
+    os::unix::process::CommandExt,
+    path::PathBuf,
+    process::{Child, Command},
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Result};
+use termion::{clear, cursor};
+
+use crate::{
+    file_watcher::FileWatcher,
+    schema::project::Project,
+    util::{
+        import_project_value, interrupt_received, project_dir, signal_process_group, Console,
+        MessageSeverity,
+    },
+};
+
+/// Interval in which the watcher checks for changes and the state of the current run
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Time without further changes before a run is restarted
+const DEBOUNCE_DURATION: Duration = Duration::from_millis(300);
+/// Time a run gets to terminate before it is killed
+const STOP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Executes watch mode for a job
+pub fn watch_job_mode(
+    projectfile: String,
+    jobname: String,
+    output_args: Vec<String>,
+    clear: bool,
+    console: Console,
+) -> Result<()> {
+    let project_data = import_project_value(&projectfile)?;
+    let project = Project::import_project(project_data)?;
+    project.check_semantics(console)?;
+
+    let Some(job) = project.jobs.get(&jobname) else {
+        return Err(anyhow!(format!("Job with name \"{}\" not found", &jobname)));
+    };
+
+    // Collect the globs of the job and the tasks of all of its steps and hooks, including `for_each` globs
+    let mut globs: Vec<String> = job.watch.clone().unwrap_or_default();
+    let lifecycle_steps = job.lifecycle_steps();
+    let taskcalls = job.tasks.iter().chain(
+        lifecycle_steps
+            .iter()
+            .flat_map(|(_, steps)| steps.iter().flatten()),
+    );
+    for taskcall in taskcalls {
+        if let Some(task_globs) = project
+            .tasks
+            .get(&taskcall.task)
+            .and_then(|task| task.watch.as_ref())
+        {
+            globs.extend(task_globs.iter().cloned());
+        }
+        if let Some(for_each) = &taskcall.for_each {
+            globs.push(for_each.glob.clone());
+        }
+    }
+
+    let run_args = vec![String::from("run"), jobname];
+    watch(&projectfile, &globs, run_args, output_args, clear, console)
+}
+
+/// Executes watch mode for a single task
+pub fn watch_task_mode(
+    projectfile: String,
+    taskname: String,
+    arguments: Vec<String>,
+    output_args: Vec<String>,
+    clear: bool,
+    console: Console,
+) -> Result<()> {
+    let project_data = import_project_value(&projectfile)?;
+    let project = Project::import_project(project_data)?;
+    project.check_semantics(console)?;
+
+    let Some(task) = project.tasks.get(&taskname) else {
+        return Err(anyhow!(format!(
+            "Task with name \"{}\" not found",
+            taskname
+        )));
+    };
+    let globs: Vec<String> = task.watch.clone().unwrap_or_default();
+
+    let mut run_args = vec![String::from("run-task"), taskname, String::from("--")];
+    run_args.extend(arguments);
+    watch(&projectfile, &globs, run_args, output_args, clear, console)
+}
+
+/// Runs `setup` with the given arguments and restarts it whenever watched files change
+fn watch(
+    projectfile: &str,
+    globs: &[String],
+    run_args: Vec<String>,
+    output_args: Vec<String>,
+    clear: bool,
+    console: Console,
+) -> Result<()> {
+    let project_dir = project_dir(projectfile)?;
+    let mut watcher = FileWatcher::new(&project_dir, globs)?;
+
+    // Arguments for `setup` to execute a single run
+    let mut args = vec![projectfile.to_string()];
+    args.extend(output_args);
+    args.extend(run_args);
+
+    loop {
+        if clear {
+            print!("{}{}", clear::All, cursor::Goto(1, 1));
+            let _ = std::io::stdout().flush();
+        }
+
+        console.print_message(
+            MessageSeverity::Info,
+            format!("Watching for changes in \"{}\"", project_dir.display()),
+        );
+        let mut run = spawn_run(&args)?;
+        let mut run_finished = false;
+        let mut last_change: Option<Instant> = None;
+
+        // Wait until changes settle down
+        loop {
+            thread::sleep(POLL_INTERVAL);
+
+            if interrupt_received() {
+                stop_run(&mut run);
+                return Ok(());
+            }
+
+            if !run_finished {
+                if let Some(status) = run.try_wait()? {
+                    run_finished = true;
+                    if status.success() {
+                        console.print_message(
+                            MessageSeverity::Success,
+                            String::from("Run finished, waiting for changes..."),
+                        );
+                    } else {
+                        eprintln!(
+                            "{}",
+                            console
+                                .format_error(String::from("Run failed, waiting for changes..."))
+                        );
+                    }
+                }
+            }
+
+            let changed_files = watcher.poll_changes()?;
+            if let Some(changed_file) = changed_files.first() {
+                if last_change.is_none() {
+                    console.print_message(
+                        MessageSeverity::Info,
+                        format!("Detected change in \"{}\"", changed_file.display()),
+                    );
+                }
+                last_change = Some(Instant::now());
+            }
+
+            if last_change.is_some_and(|last_change| last_change.elapsed() >= DEBOUNCE_DURATION) {
+                break;
+            }
+        }
+
+        if !run_finished {
+            console.print_message(
+                MessageSeverity::Info,
+                String::from("Stopping current run..."),
+            );
+            stop_run(&mut run);
+        }
+    }
+}
+
+/// Spawns `setup` with the given arguments in its own process group, so the whole run can be stopped at once
+fn spawn_run(args: &[String]) -> Result<Child> {
+    let executable = setup_executable()?;
+    let child = Command::new(executable).args(args).process_group(0).spawn();
+
+    match child {
+        Ok(child) => Ok(child),
+        Err(error) => Err(anyhow!(format!("Cannot spawn run ({})", error))),
+    }
+}
+
+/// Returns the `setup` executable which executes the runs. If `setup` is embedded as a library, the current
+/// executable is a different program and `setup` is looked up in the `PATH` instead.
+fn setup_executable() -> Result<PathBuf> {
+    let name = env!("CARGO_PKG_NAME");
+    if let Ok(executable) = std::env::current_exe() {
+        if executable
+            .file_name()
+            .is_some_and(|file_name| file_name == name)
+        {
+            return Ok(executable);
+        }
+    }
+
+    std::env::var_os("PATH")
+        .and_then(|paths| {
+            std::env::split_paths(&paths)
+                .map(|dir| dir.join(name))
+                .find(|path| path.is_file())
+        })
+        .ok_or_else(|| {
+            anyhow!(format!(
+                "Cannot find the \"{}\" executable for the runs",
+                name
+            ))
+        })
+}
+
+/// Interrupts the process group of the run, so `finally` hooks are executed, and kills it if it does not stop in time
+fn stop_run(run: &mut Child) {
+    let process_group = run.id() as libc::pid_t;
+    signal_process_group(process_group, libc::SIGINT);
+
+    let deadline = Instant::now() + STOP_TIMEOUT;
+    while Instant::now() < deadline {
+        let run_finished = matches!(run.try_wait(), Ok(Some(_)));
+        if run_finished && !signal_process_group(process_group, 0) {
+            return;
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+
+    signal_process_group(process_group, libc::SIGKILL);
+    let _ = run.wait();
+}
@@ -0,0 +1,139 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{BufRead, BufReader, BufWriter, Write},
+    os::unix::fs::OpenOptionsExt,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Result};
+
+use crate::events::OutputStream;
+
+/// Amount of bytes kept in memory before the output is moved to a temporary file
+const MEMORY_LIMIT: usize = 1024 * 1024;
+
+/// Collects the output lines of a task to print them as one block later
+pub struct OutputBuffer {
+    /// Name of the temporary file, unique within the process
+    name: String,
+    lines: Vec<(OutputStream, String)>,
+    size: usize,
+    spill: Option<(PathBuf, BufWriter<File>)>,
+    /// Reason the output could not be moved to a temporary file. The output is kept in memory instead.
+    spill_error: Option<anyhow::Error>,
+}
+
+impl OutputBuffer {
+    pub fn new(name: String) -> OutputBuffer {
+        OutputBuffer {
+            name,
+            lines: Vec::new(),
+            size: 0,
+            spill: None,
+            spill_error: None,
+        }
+    }
+
+    /// Appends a line. Once the buffered output exceeds `MEMORY_LIMIT`, it is written to a temporary file.
+    /// Fails only if the line was not buffered.
+    pub fn push(&mut self, stream: OutputStream, line: &str) -> Result<()> {
+        if let Some((_, writer)) = self.spill.as_mut() {
+            return Self::write_line(writer, stream, line);
+        }
+
+        self.size += line.len();
+        self.lines.push((stream, line.to_string()));
+        if self.size > MEMORY_LIMIT && self.spill_error.is_none() {
+            let path = std::env::temp_dir().join(format!(
+                "setup-output-{}-{}",
+                std::process::id(),
+                self.name
+            ));
+            match self.spill_lines(&path) {
+                Ok(writer) => {
+                    self.lines.clear();
+                    self.spill = Some((path, writer));
+                }
+                Err(error) => self.spill_error = Some(error),
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the lines kept in memory to a new temporary file, which only the current user can read.
+    /// The file is removed again if writing fails, but never if it existed before.
+    fn spill_lines(&self, path: &Path) -> Result<BufWriter<File>> {
+        let file = match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(path)
+        {
+            Ok(file) => file,
+            Err(error) => {
+                return Err(anyhow!(format!(
+                    "Cannot create temporary output file \"{}\" ({})",
+                    path.display(),
+                    error
+                )))
+            }
+        };
+        let mut writer = BufWriter::new(file);
+        let written = self
+            .lines
+            .iter()
+            .try_for_each(|(stream, line)| Self::write_line(&mut writer, *stream, line))
+            .and_then(|_| Ok(writer.flush()?));
+        if let Err(error) = written {
+            let _ = fs::remove_file(path);
+            return Err(error);
+        }
+        Ok(writer)
+    }
+
+    /// Passes all buffered lines in their original order to the callback and removes the temporary file.
+    /// Fails if the output could not be moved to or read from the temporary file.
+    pub fn drain(&mut self, mut callback: impl FnMut(OutputStream, &str)) -> Result<()> {
+        for (stream, line) in self.lines.drain(..) {
+            callback(stream, &line);
+        }
+        let Some((path, mut writer)) = self.spill.take() else {
+            return match self.spill_error.take() {
+                Some(error) => Err(error),
+                None => Ok(()),
+            };
+        };
+
+        writer.flush()?;
+        drop(writer);
+        let reader = BufReader::new(File::open(&path)?);
+        for line in reader.lines() {
+            let line = line?;
+            match line.split_at_checked(1) {
+                Some(("E", line)) => callback(OutputStream::Stderr, line),
+                Some((_, line)) => callback(OutputStream::Stdout, line),
+                None => {}
+            }
+        }
+        fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    /// Writes a line prefixed with the stream it was written to
+    fn write_line(writer: &mut BufWriter<File>, stream: OutputStream, line: &str) -> Result<()> {
+        let prefix = match stream {
+            OutputStream::Stdout => "O",
+            OutputStream::Stderr => "E",
+        };
+        writeln!(writer, "{}{}", prefix, line)?;
+        Ok(())
+    }
+}
+
+impl Drop for OutputBuffer {
+    fn drop(&mut self) {
+        if let Some((path, _)) = self.spill.take() {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Result};
+
+use crate::reporter::TaskInfo;
+
+/// Width of a process id, which is at most 4194304 on Linux
+const PID_WIDTH: usize = 7;
+/// Width of the elapsed time of tasks running less than 1000 seconds, e.g. "999.9s"
+const ELAPSED_WIDTH: usize = 6;
+/// Width of the local time as `HH:MM:SS`
+const TIME_WIDTH: usize = 8;
+
+/// Single part of a prefix template
+#[derive(Clone)]
+enum PrefixPart {
+    Text(String),
+    Label,
+    Task,
+    Command,
+    Pid,
+    Elapsed,
+    Time,
+}
+
+/// Template of the prefix printed in front of every output line of a task.
+/// Supported placeholders are `{label}`, `{task}`, `{command}`, `{pid}`, `{elapsed}` and `{time}`.
+#[derive(Clone)]
+pub struct PrefixTemplate {
+    parts: Vec<PrefixPart>,
+}
+
+impl Default for PrefixTemplate {
+    fn default() -> Self {
+        PrefixTemplate {
+            parts: vec![PrefixPart::Command],
+        }
+    }
+}
+
+impl PrefixTemplate {
+    /// Parses a template, failing on unknown placeholders and unclosed braces
+    pub fn parse(template: &str) -> Result<PrefixTemplate> {
+        let mut parts: Vec<PrefixPart> = Vec::new();
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            if start > 0 {
+                parts.push(PrefixPart::Text(rest[..start].to_string()));
+            }
+            let Some(end) = rest[start..].find('}') else {
+                return Err(anyhow!(format!(
+                    "Unclosed placeholder in prefix template \"{}\"",
+                    template
+                )));
+            };
+            let part = match &rest[start + 1..start + end] {
+                "label" => PrefixPart::Label,
+                "task" => PrefixPart::Task,
+                "command" => PrefixPart::Command,
+                "pid" => PrefixPart::Pid,
+                "elapsed" => PrefixPart::Elapsed,
+                "time" => PrefixPart::Time,
+                placeholder => {
+                    return Err(anyhow!(format!(
+                        "Unknown placeholder \"{{{}}}\" in prefix template \"{}\"",
+                        placeholder, template
+                    )))
+                }
+            };
+            parts.push(part);
+            rest = &rest[start + end + 1..];
+        }
+        if !rest.is_empty() {
+            parts.push(PrefixPart::Text(rest.to_string()));
+        }
+        Ok(PrefixTemplate { parts })
+    }
+
+    /// Renders the prefix of an output line of the task. Tasks without a label use their name as label.
+    pub fn render(&self, task: &TaskInfo) -> String {
+        self.parts.iter().map(|part| part.render(task)).collect()
+    }
+
+    /// Returns the width of the prefix of the task. Parts which change while the task runs are counted with
+    /// their largest usual width, so the width is known before the task starts.
+    pub fn max_width(&self, task: &TaskInfo) -> usize {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                PrefixPart::Pid => PID_WIDTH,
+                PrefixPart::Elapsed => ELAPSED_WIDTH,
+                PrefixPart::Time => TIME_WIDTH,
+                part => part.render(task).chars().count(),
+            })
+            .sum()
+    }
+}
+
+impl PrefixPart {
+    /// Renders the part for the task
+    fn render(&self, task: &TaskInfo) -> String {
+        match self {
+            PrefixPart::Text(text) => text.clone(),
+            PrefixPart::Label => task.label.clone().unwrap_or_else(|| task.task.clone()),
+            PrefixPart::Task => task.task.clone(),
+            PrefixPart::Command => task.command.clone(),
+            PrefixPart::Pid => task
+                .pid
+                .map(|pid| pid.to_string())
+                .unwrap_or_else(|| String::from("-")),
+            PrefixPart::Elapsed => task
+                .start_time
+                .map(|start_time| format!("{:.1}s", start_time.elapsed().as_secs_f64()))
+                .unwrap_or_else(|| String::from("-")),
+            PrefixPart::Time => local_time(),
+        }
+    }
+}
+
+/// Returns the current local wall-clock time as `HH:MM:SS`
+fn local_time() -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as libc::time_t)
+        .unwrap_or_default();
+    // SAFETY: `localtime_r` only writes to the given `tm` struct, which is fully initialized by it
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    if unsafe { libc::localtime_r(&now, &mut tm) }.is_null() {
+        return String::from("--:--:--");
+    }
+    format!("{:02}:{:02}:{:02}", tm.tm_hour, tm.tm_min, tm.tm_sec)
+}
@@ -0,0 +1,214 @@
+use std::{
+    fmt::Display,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::{schema::validation::Diagnostic, util::format_table};
+
+/// Final state of a single task
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    Success,
+    Failed,
+    Skipped,
+    NotStarted,
+}
+
+impl Display for TaskStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let status = match self {
+            TaskStatus::Success => "success",
+            TaskStatus::Failed => "failed",
+            TaskStatus::Skipped => "skipped",
+            TaskStatus::NotStarted => "not started",
+        };
+        write!(f, "{}", status)
+    }
+}
+
+/// Result of a single task execution
+#[derive(Serialize)]
+pub struct TaskReport {
+    pub task: String,
+    pub label: Option<String>,
+    pub command: String,
+    pub status: TaskStatus,
+    pub pid: Option<u32>,
+    pub exit_code: Option<i32>,
+    pub signal: Option<i32>,
+    pub started_at: Option<f64>,
+    pub finished_at: Option<f64>,
+    pub duration_secs: Option<f64>,
+    pub skip_reason: Option<String>,
+    pub stderr_tail: Vec<String>,
+}
+
+/// Result of a job execution
+#[derive(Serialize)]
+pub struct JobReport {
+    pub job: String,
+    pub parallel: bool,
+    pub success: bool,
+    /// Reason the job failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub started_at: f64,
+    pub finished_at: f64,
+    pub duration_secs: f64,
+    pub tasks: Vec<TaskReport>,
+    pub hooks: Vec<HookReport>,
+}
+
+impl JobReport {
+    /// Returns the lines of the summary: a table with the final state of every task, followed by the wall
+    /// time and the summed task time
+    pub fn summary_lines(&self) -> Vec<String> {
+        let hook_tasks = self.hooks.iter().flat_map(|hook| {
+            hook.tasks
+                .iter()
+                .map(|task| (format!("{} ({})", task.task, hook.hook), task))
+        });
+        let tasks: Vec<(String, &TaskReport)> = self
+            .tasks
+            .iter()
+            .map(|task| (task.task.clone(), task))
+            .chain(hook_tasks)
+            .collect();
+
+        let rows: Vec<Vec<String>> = tasks
+            .iter()
+            .map(|(name, task)| {
+                let exit = match (task.exit_code, task.signal) {
+                    (Some(code), _) => code.to_string(),
+                    (None, Some(signal)) => format!("signal {}", signal),
+                    (None, None) => String::from("-"),
+                };
+                vec![
+                    name.clone(),
+                    task.label.clone().unwrap_or_default(),
+                    task.status.to_string(),
+                    exit,
+                    task.duration_secs
+                        .map(|duration| format!("{:.2}s", duration))
+                        .unwrap_or_default(),
+                    task.skip_reason.clone().unwrap_or_default(),
+                ]
+            })
+            .collect();
+
+        let header = [
+            "TASK",
+            "LABEL",
+            "STATUS",
+            "EXIT",
+            "DURATION",
+            "SKIPPED REASON",
+        ];
+        let mut lines = vec![format!("Summary of job \"{}\"", self.job)];
+        lines.extend(
+            format_table(&header, &rows)
+                .into_iter()
+                .map(|line| format!("  {}", line)),
+        );
+
+        let task_time: f64 = tasks
+            .iter()
+            .filter_map(|(_, task)| task.duration_secs)
+            .sum();
+        lines.push(format!(
+            "Total wall time: {:.2}s, summed task time: {:.2}s",
+            self.duration_secs, task_time
+        ));
+        lines
+    }
+}
+
+/// Result of an executed job hook
+#[derive(Serialize)]
+pub struct HookReport {
+    pub hook: String,
+    pub success: bool,
+    pub tasks: Vec<TaskReport>,
+}
+
+/// Execution plan of a single task
+#[derive(Serialize)]
+pub struct StepPlan {
+    pub task: String,
+    pub label: Option<String>,
+    pub command: String,
+    pub work_dir: String,
+    pub env: Vec<String>,
+    pub service: bool,
+    pub actions: Vec<String>,
+}
+
+/// Execution plan of a job
+#[derive(Serialize)]
+pub struct JobPlan {
+    pub job: String,
+    pub parallel: bool,
+    pub steps: Vec<StepPlan>,
+    pub hooks: Vec<HookPlan>,
+}
+
+/// Execution plan of a job hook
+#[derive(Serialize)]
+pub struct HookPlan {
+    pub hook: String,
+    pub steps: Vec<StepPlan>,
+}
+
+/// Result of the validation of a project file
+#[derive(Serialize)]
+pub struct ValidationReport {
+    pub file: String,
+    pub valid: bool,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Result of a job or task which could not be started, e.g. because of missing arguments
+#[derive(Serialize)]
+pub struct ErrorReport {
+    pub success: bool,
+    pub error: String,
+}
+
+/// Listing entry of a job
+#[derive(Serialize)]
+pub struct JobEntry {
+    pub name: String,
+    pub description: Option<String>,
+    pub tasks: Vec<String>,
+    pub tags: Vec<String>,
+    pub parallel: bool,
+}
+
+/// Listing entry of a task
+#[derive(Serialize)]
+pub struct TaskEntry {
+    pub name: String,
+    pub description: Option<String>,
+    pub command: String,
+    pub args: Vec<String>,
+    pub required_call_args: u8,
+    pub tags: Vec<String>,
+    pub hidden: bool,
+}
+
+/// Converts a point in time to seconds since the Unix epoch
+pub fn unix_secs(time: SystemTime) -> f64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_secs_f64()
+}
+
+/// Prints the given value as pretty json to stdout
+pub fn print_json<T: Serialize>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
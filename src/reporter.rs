@@ -0,0 +1,342 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+
+use termion::color;
+
+use crate::{
+    events::OutputStream,
+    prefix::PrefixTemplate,
+    report::{JobReport, TaskReport, TaskStatus},
+    util::{Console, MessageSeverity},
+};
+
+/// Task as seen by reporters
+#[derive(Clone)]
+pub struct TaskInfo {
+    /// Identifier of the task, unique within the process
+    pub id: usize,
+    pub task: String,
+    pub label: Option<String>,
+    pub command: String,
+    /// Process id of the spawned command, built-in operations have none
+    pub pid: Option<u32>,
+    pub start_time: Option<Instant>,
+    /// Position of the task within a parallel job, used to tell the output of the tasks apart
+    pub parallel_index: Option<usize>,
+}
+
+impl TaskInfo {
+    /// Returns the command line of the task followed by its label, if any
+    pub fn display_name(&self) -> String {
+        match &self.label {
+            Some(label) => format!("{} [{}]", self.command, label),
+            None => self.command.clone(),
+        }
+    }
+}
+
+/// Importance of a message about the execution which is not tied to a single event
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MessageLevel {
+    Error,
+    Warning,
+    /// Details about the execution, printed with `--verbose`
+    Verbose,
+    /// Debug details like input hashes, printed with `-vv`
+    Debug,
+}
+
+/// Observer of a job execution. Methods may be called from several threads and do nothing by default.
+pub trait Reporter: Send + Sync {
+    /// Called before the first step of a job is executed
+    fn job_started(&self, _job: &str) {}
+
+    /// Called with the tasks of the job before its first step, and with the runs of a `for_each` step once
+    /// the files of the step are expanded
+    fn tasks_planned(&self, _tasks: &[TaskInfo]) {}
+
+    /// Called after a task was spawned or its built-in operation was started
+    fn task_started(&self, _task: &TaskInfo, _pid: Option<u32>) {}
+
+    /// Called instead of `task_started` for tasks which are not executed
+    fn task_skipped(&self, _task: &TaskInfo, _reason: &str) {}
+
+    /// Called for every line a task writes to stdout or stderr
+    fn output_line(&self, _task: &TaskInfo, _stream: OutputStream, _line: &str) {}
+
+    /// Called after a started task exited
+    fn task_finished(&self, _task: &TaskInfo, _report: &TaskReport) {}
+
+    /// Called once the readiness probe of a started service passed
+    fn service_ready(&self, _task: &TaskInfo) {}
+
+    /// Called before a running service is stopped at the end of the job
+    fn service_stopping(&self, _task: &TaskInfo) {}
+
+    /// Called before the steps of a hook like `on_failure` are executed
+    fn hook_started(&self, _job: &str, _hook: &str) {}
+
+    /// Called after the steps of a hook finished, with the reason if the hook failed
+    fn hook_finished(&self, _job: &str, _hook: &str, _error: Option<&str>) {}
+
+    /// Called for errors, warnings and details like failed tasks of parallel jobs or unusable cache files
+    fn message(&self, _level: MessageLevel, _message: &str) {}
+
+    /// Called after the job and its hooks finished
+    fn job_finished(&self, _report: &JobReport) {}
+}
+
+/// Forwards every call to all contained reporters in order
+#[derive(Default)]
+pub struct MultiReporter {
+    reporters: Vec<Arc<dyn Reporter>>,
+}
+
+impl MultiReporter {
+    pub fn new(reporters: Vec<Arc<dyn Reporter>>) -> MultiReporter {
+        MultiReporter { reporters }
+    }
+
+    /// Adds a reporter which is called after the already contained ones
+    pub fn push(&mut self, reporter: Arc<dyn Reporter>) {
+        self.reporters.push(reporter);
+    }
+}
+
+impl Reporter for MultiReporter {
+    fn job_started(&self, job: &str) {
+        self.reporters.iter().for_each(|r| r.job_started(job));
+    }
+
+    fn tasks_planned(&self, tasks: &[TaskInfo]) {
+        self.reporters.iter().for_each(|r| r.tasks_planned(tasks));
+    }
+
+    fn task_started(&self, task: &TaskInfo, pid: Option<u32>) {
+        self.reporters
+            .iter()
+            .for_each(|r| r.task_started(task, pid));
+    }
+
+    fn task_skipped(&self, task: &TaskInfo, reason: &str) {
+        self.reporters
+            .iter()
+            .for_each(|r| r.task_skipped(task, reason));
+    }
+
+    fn output_line(&self, task: &TaskInfo, stream: OutputStream, line: &str) {
+        self.reporters
+            .iter()
+            .for_each(|r| r.output_line(task, stream, line));
+    }
+
+    fn task_finished(&self, task: &TaskInfo, report: &TaskReport) {
+        self.reporters
+            .iter()
+            .for_each(|r| r.task_finished(task, report));
+    }
+
+    fn service_ready(&self, task: &TaskInfo) {
+        self.reporters.iter().for_each(|r| r.service_ready(task));
+    }
+
+    fn service_stopping(&self, task: &TaskInfo) {
+        self.reporters.iter().for_each(|r| r.service_stopping(task));
+    }
+
+    fn hook_started(&self, job: &str, hook: &str) {
+        self.reporters
+            .iter()
+            .for_each(|r| r.hook_started(job, hook));
+    }
+
+    fn hook_finished(&self, job: &str, hook: &str, error: Option<&str>) {
+        self.reporters
+            .iter()
+            .for_each(|r| r.hook_finished(job, hook, error));
+    }
+
+    fn message(&self, level: MessageLevel, message: &str) {
+        self.reporters
+            .iter()
+            .for_each(|r| r.message(level, message));
+    }
+
+    fn job_finished(&self, report: &JobReport) {
+        self.reporters.iter().for_each(|r| r.job_finished(report));
+    }
+}
+
+/// ANSI colours of the output prefixes of parallel tasks, leaving out the red and yellow of the message labels
+const PREFIX_COLORS: [u8; 8] = [6, 5, 2, 4, 14, 13, 10, 12];
+
+/// Coloured console output of the command line interface
+pub struct ConsoleReporter {
+    console: Console,
+    /// Do not print the output of the tasks
+    silent_children: bool,
+    /// Print a table with the final state of every task once the job finished
+    summary: bool,
+    prefix: PrefixTemplate,
+    /// Width of the longest prefix of the planned tasks of the job, shorter prefixes are padded to it
+    prefix_width: AtomicUsize,
+}
+
+impl ConsoleReporter {
+    pub fn new(
+        console: Console,
+        silent_children: bool,
+        summary: bool,
+        prefix: PrefixTemplate,
+    ) -> ConsoleReporter {
+        ConsoleReporter {
+            console,
+            silent_children,
+            summary,
+            prefix,
+            prefix_width: AtomicUsize::new(0),
+        }
+    }
+
+    /// Renders the padded prefix of an output line. Tasks of parallel jobs get a colour by their position.
+    fn format_prefix(&self, task: &TaskInfo, severity: MessageSeverity) -> String {
+        let prefix = self.prefix.render(task);
+        let length = prefix.chars().count();
+        let width = self.prefix_width.load(Ordering::Relaxed);
+        let padding = " ".repeat(width.saturating_sub(length));
+        match task.parallel_index {
+            Some(index) => {
+                let color = color::AnsiValue(PREFIX_COLORS[index % PREFIX_COLORS.len()]);
+                let prefix =
+                    self.console
+                        .paint(&prefix, &color, self.console.uses_stderr(severity));
+                format!("{}{}", prefix, padding)
+            }
+            None => format!("{}{}", prefix, padding),
+        }
+    }
+}
+
+impl Reporter for ConsoleReporter {
+    fn job_started(&self, job: &str) {
+        self.console
+            .print_message(MessageSeverity::Info, format!("Executing job \"{}\"", job));
+        self.prefix_width.store(0, Ordering::Relaxed);
+    }
+
+    fn tasks_planned(&self, tasks: &[TaskInfo]) {
+        let width = tasks
+            .iter()
+            .map(|task| self.prefix.max_width(task))
+            .max()
+            .unwrap_or_default();
+        self.prefix_width.fetch_max(width, Ordering::Relaxed);
+    }
+
+    fn task_started(&self, task: &TaskInfo, _pid: Option<u32>) {
+        self.console.print_message(
+            MessageSeverity::Info,
+            format!("Executing task \"{}\"...", task.display_name()),
+        );
+    }
+
+    fn task_skipped(&self, task: &TaskInfo, reason: &str) {
+        self.console.print_message(
+            MessageSeverity::Info,
+            format!(
+                "Task \"{}\" skipped ({}), continuing...",
+                task.display_name(),
+                reason
+            ),
+        );
+    }
+
+    fn output_line(&self, task: &TaskInfo, stream: OutputStream, line: &str) {
+        if self.silent_children {
+            return;
+        }
+        let severity = match stream {
+            OutputStream::Stdout => MessageSeverity::ChildInfo,
+            OutputStream::Stderr => MessageSeverity::ChildError,
+        };
+        self.console.print_message(
+            severity,
+            format!("{} -> {}", self.format_prefix(task, severity), line),
+        );
+    }
+
+    fn task_finished(&self, task: &TaskInfo, report: &TaskReport) {
+        if report.status == TaskStatus::Success {
+            self.console.print_message(
+                MessageSeverity::Success,
+                format!("Task \"{}\" executed successfully", task.display_name()),
+            );
+        }
+    }
+
+    fn service_ready(&self, task: &TaskInfo) {
+        self.console.print_message(
+            MessageSeverity::Info,
+            format!("Service \"{}\" is ready", task.display_name()),
+        );
+    }
+
+    fn service_stopping(&self, task: &TaskInfo) {
+        self.console.print_message(
+            MessageSeverity::Info,
+            format!("Stopping service \"{}\"...", task.display_name()),
+        );
+    }
+
+    fn hook_started(&self, job: &str, hook: &str) {
+        self.console.print_message(
+            MessageSeverity::Info,
+            format!("Executing hook \"{}\" of job \"{}\"", hook, job),
+        );
+    }
+
+    fn hook_finished(&self, job: &str, hook: &str, error: Option<&str>) {
+        if let Some(error) = error {
+            eprintln!(
+                "{}",
+                self.console.format_error(format!(
+                    "Hook \"{}\" of job \"{}\" failed: {}",
+                    hook, job, error
+                ))
+            );
+        }
+    }
+
+    fn message(&self, level: MessageLevel, message: &str) {
+        let severity = match level {
+            MessageLevel::Error => {
+                eprintln!("{}", self.console.format_error(message.to_string()));
+                return;
+            }
+            MessageLevel::Warning => MessageSeverity::Warning,
+            MessageLevel::Verbose => MessageSeverity::Verbose,
+            MessageLevel::Debug => MessageSeverity::Debug,
+        };
+        self.console.print_message(severity, message.to_string());
+    }
+
+    fn job_finished(&self, report: &JobReport) {
+        if self.summary {
+            self.print_summary(report);
+        }
+    }
+}
+
+impl ConsoleReporter {
+    /// Prints a table with the final state of every task, followed by the wall time and the summed task time
+    fn print_summary(&self, report: &JobReport) {
+        for line in report.summary_lines() {
+            self.console.print_message(MessageSeverity::Info, line);
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Portable file operation executed by `setup` itself. The operands are passed as arguments of the task
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum Builtin {
+    /// Copies the files and directories matching the globs to the last argument, like `cp -r`
+    Copy,
+    /// Removes the files and directories matching the globs, like `rm -rf`
+    Remove,
+    /// Creates the directories including their parents, like `mkdir -p`
+    Mkdir,
+    /// Creates a symbolic link at the second argument pointing to the first, like `ln -sf`
+    Symlink,
+    /// Writes the second argument to the file given by the first argument
+    WriteFile,
+}
+
+impl Builtin {
+    /// Returns the name of the operation as used in the project file
+    pub fn name(&self) -> &'static str {
+        match self {
+            Builtin::Copy => "copy",
+            Builtin::Remove => "remove",
+            Builtin::Mkdir => "mkdir",
+            Builtin::Symlink => "symlink",
+            Builtin::WriteFile => "write_file",
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use std::{
+    path::Path,
+    process::{Command, Stdio},
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Condition which must be met for a step to be executed
+#[derive(Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Condition {
+    /// Met if the file or directory exists. Relative paths are resolved against the working directory
+    FileExists(String),
+    /// Met if the environment variable is set and, if given, has the expected value
+    Env {
+        /// Name of the environment variable
+        name: String,
+        /// Expected value of the environment variable
+        #[serde(skip_serializing_if = "Option::is_none")]
+        equals: Option<String>,
+    },
+    /// Met if the command exits successfully
+    Command {
+        /// Command to execute
+        command: String,
+        /// Arguments to pass to the command
+        #[serde(skip_serializing_if = "Option::is_none")]
+        args: Option<Vec<String>>,
+    },
+    /// Met if all conditions are met
+    All(Vec<Condition>),
+    /// Met if at least one condition is met
+    Any(Vec<Condition>),
+    /// Met if the condition is not met
+    Not(Box<Condition>),
+}
+
+impl Condition {
+    /// Evaluates the condition. Files and commands are resolved against the given working directory.
+    pub fn evaluate(&self, work_dir: &Path) -> bool {
+        match self {
+            Condition::FileExists(path) => work_dir.join(path).exists(),
+            Condition::Env { name, equals } => match (std::env::var(name), equals) {
+                (Ok(value), Some(expected)) => &value == expected,
+                (Ok(_), None) => true,
+                (Err(_), _) => false,
+            },
+            Condition::Command { command, args } => Command::new(command)
+                .args(args.iter().flatten())
+                .current_dir(work_dir)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .is_ok_and(|status| status.success()),
+            Condition::All(conditions) => conditions
+                .iter()
+                .all(|condition| condition.evaluate(work_dir)),
+            Condition::Any(conditions) => conditions
+                .iter()
+                .any(|condition| condition.evaluate(work_dir)),
+            Condition::Not(condition) => !condition.evaluate(work_dir),
+        }
+    }
+}
@@ -1,10 +1,92 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use super::task_call::TaskCall;
+use super::{output_mode::OutputMode, task_call::TaskCall};
 
-/// Represents a `Job` from a configuration file
-#[derive(Serialize, Deserialize, Clone)]
+/// Defines a single job
+#[derive(Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct Job {
+    /// Description of the job shown in listings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Tags used to filter listings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    /// Tasks which are executed when running this job
     pub tasks: Vec<TaskCall>,
+    /// Whether to run the tasks in parallel
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub parallel: Option<bool>,
+    /// How the output of the tasks is printed, defaults to `interleaved`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_mode: Option<OutputMode>,
+    /// Globs relative to the project directory which trigger a rerun in watch mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub watch: Option<Vec<String>>,
+    /// Steps executed sequentially before the tasks. A failure aborts the job
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<Vec<TaskCall>>,
+    /// Steps executed sequentially after the tasks succeeded. A failure fails the job
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<Vec<TaskCall>>,
+    /// Hook executed after the job succeeded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_success: Option<Vec<TaskCall>>,
+    /// Hook executed after the job failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_failure: Option<Vec<TaskCall>>,
+    /// Hook which is always executed at the end of the job, even after an interrupt
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finally: Option<Vec<TaskCall>>,
+}
+
+/// Hook of a job, executed after its steps depending on their outcome
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum JobHookKind {
+    OnSuccess,
+    OnFailure,
+    Finally,
+}
+
+impl JobHookKind {
+    /// Returns the name of the hook in the project file
+    pub fn name(&self) -> &'static str {
+        match self {
+            JobHookKind::OnSuccess => "on_success",
+            JobHookKind::OnFailure => "on_failure",
+            JobHookKind::Finally => "finally",
+        }
+    }
+
+    /// Checks whether the hook is executed after the given outcome of the job
+    pub fn runs_after(&self, job_succeeded: bool) -> bool {
+        match self {
+            JobHookKind::OnSuccess => job_succeeded,
+            JobHookKind::OnFailure => !job_succeeded,
+            JobHookKind::Finally => true,
+        }
+    }
+}
+
+impl Job {
+    /// Returns the hooks of the job in execution order
+    pub fn hooks(&self) -> [(JobHookKind, &Option<Vec<TaskCall>>); 3] {
+        [
+            (JobHookKind::OnSuccess, &self.on_success),
+            (JobHookKind::OnFailure, &self.on_failure),
+            (JobHookKind::Finally, &self.finally),
+        ]
+    }
+
+    /// Returns the lifecycle steps and hooks of the job by their name
+    pub fn lifecycle_steps(&self) -> [(&'static str, &Option<Vec<TaskCall>>); 5] {
+        [
+            ("before", &self.before),
+            ("after", &self.after),
+            ("on_success", &self.on_success),
+            ("on_failure", &self.on_failure),
+            ("finally", &self.finally),
+        ]
+    }
 }
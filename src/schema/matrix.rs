@@ -0,0 +1,77 @@
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Combination of matrix variables and their values
+pub type MatrixCombination = BTreeMap<String, String>;
+
+/// Expands a step into one execution per combination of variable values.
+/// Values are inserted into the arguments of the task and the step with `${matrix.<variable>}`
+#[derive(Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Matrix {
+    /// Variables mapped to all of their values
+    pub variables: BTreeMap<String, Vec<String>>,
+    /// Combinations which are removed. An entry matches all combinations containing its values
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclude: Option<Vec<MatrixCombination>>,
+    /// Additional combinations which are added after excluding combinations
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include: Option<Vec<MatrixCombination>>,
+}
+
+impl Matrix {
+    /// Returns all combinations of the matrix in a deterministic order
+    pub fn combinations(&self) -> Vec<MatrixCombination> {
+        let mut combinations: Vec<MatrixCombination> = vec![MatrixCombination::new()];
+        for (variable, values) in &self.variables {
+            combinations = combinations
+                .iter()
+                .flat_map(|combination| {
+                    values.iter().map(move |value| {
+                        let mut combination = combination.clone();
+                        combination.insert(variable.clone(), value.clone());
+                        combination
+                    })
+                })
+                .collect();
+        }
+
+        if let Some(excludes) = &self.exclude {
+            combinations.retain(|combination| {
+                !excludes.iter().any(|exclude| {
+                    exclude
+                        .iter()
+                        .all(|(variable, value)| combination.get(variable) == Some(value))
+                })
+            });
+        }
+
+        for include in self.include.iter().flatten() {
+            if !combinations.contains(include) {
+                combinations.push(include.clone());
+            }
+        }
+
+        combinations
+    }
+}
+
+/// Replaces all `${matrix.<variable>}` placeholders with the values of the combination
+pub fn substitute_matrix_values(value: &str, combination: &MatrixCombination) -> String {
+    let mut value = value.to_string();
+    for (variable, variable_value) in combination {
+        value = value.replace(&format!("${{matrix.{}}}", variable), variable_value);
+    }
+    value
+}
+
+/// Formats a combination as a label like `feature=a, os=linux`
+pub fn matrix_label(combination: &MatrixCombination) -> String {
+    combination
+        .iter()
+        .map(|(variable, value)| format!("{}={}", variable, value))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
@@ -1,5 +1,12 @@
+pub mod builtin;
+pub mod condition;
 pub mod job;
+pub mod matrix;
+pub mod output_mode;
 pub mod project;
+pub mod service;
 pub mod settings;
 pub mod task;
 pub mod task_call;
+pub mod validation;
+pub mod wait_for;
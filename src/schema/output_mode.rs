@@ -0,0 +1,29 @@
+use std::fmt::Display;
+
+use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Defines how the output of the tasks of a job is printed
+#[derive(Serialize, Deserialize, JsonSchema, ValueEnum, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputMode {
+    /// Lines are printed as soon as they are written, mixing the output of parallel tasks
+    #[default]
+    Interleaved,
+    /// The output of each task is printed as one block when the task finishes
+    Grouped,
+    /// Like `grouped`, but only the output of failing tasks is printed
+    GroupedOnFailure,
+}
+
+impl Display for OutputMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mode = match self {
+            OutputMode::Interleaved => "interleaved",
+            OutputMode::Grouped => "grouped",
+            OutputMode::GroupedOnFailure => "grouped-on-failure",
+        };
+        write!(f, "{}", mode)
+    }
+}
@@ -1,22 +1,36 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::OnceLock};
 
 use anyhow::{anyhow, Result};
+use jsonschema::{Draft, JSONSchema};
+use schemars::{generate::SchemaSettings, JsonSchema};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::JSONSCHEMA;
-
 use super::{job::Job, settings::Settings, task::Task};
 
-/// Represents the content of a `Project` from a configuration file
-#[derive(Serialize, Deserialize)]
+/// Compiled JSON schema of the `Project` type, built on first use
+static JSONSCHEMA: OnceLock<JSONSchema> = OnceLock::new();
+
+/// Project schema
+#[derive(Serialize, Deserialize, JsonSchema)]
 pub struct Project {
+    /// Project settings
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub settings: Option<Settings>,
+    /// Defines all available jobs
     pub jobs: HashMap<String, Job>,
+    /// Defines all tasks
     pub tasks: HashMap<String, Task>,
 }
 
 impl Project {
+    /// Generates the json schema of a project file from the `Project` type
+    pub fn generate_jsonschema() -> Value {
+        let generator = SchemaSettings::draft07().into_generator();
+        let schema = generator.into_root_schema_for::<Project>();
+        schema.to_value()
+    }
+
     /// Imports a `Project` from a JSON string
     pub fn import_project(project_data: Value) -> Result<Project> {
         // Validate project file against jsonschema
@@ -31,7 +45,12 @@ impl Project {
 
     /// Validates a `Project` from a JSON string. Panics if the project is invalid.
     pub fn validate_project(project: &Value) -> Result<()> {
-        let schema = JSONSCHEMA.get().unwrap();
+        let schema = JSONSCHEMA.get_or_init(|| {
+            JSONSchema::options()
+                .with_draft(Draft::Draft7)
+                .compile(&Self::generate_jsonschema())
+                .unwrap_or_else(|error| panic!("Error compiling JSON schema: {}", error))
+        });
 
         if !schema.is_valid(project) {
             Err(anyhow!(String::from(
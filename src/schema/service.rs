@@ -0,0 +1,71 @@
+use std::{
+    net::{TcpStream, ToSocketAddrs},
+    path::Path,
+    sync::atomic::AtomicBool,
+    time::{Duration, Instant},
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::wait_for::command_succeeds;
+
+/// Seconds to wait for a service to become ready if no timeout is given
+pub const DEFAULT_READY_TIMEOUT_SECS: u64 = 30;
+/// Timeout of a single connection attempt of a TCP port probe
+const TCP_CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Defines when a service is ready
+#[derive(Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Readiness {
+    /// Probe which must pass for the service to be ready
+    pub probe: ReadinessProbe,
+    /// Seconds to wait for the probe to pass. Defaults to 30
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+}
+
+/// Probe which is repeated until it passes
+#[derive(Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum ReadinessProbe {
+    /// Passes once a TCP connection to the address can be established, e.g. "127.0.0.1:8080"
+    TcpPort(String),
+    /// Passes once the file or directory exists. Relative paths are resolved against the working directory
+    FileExists(String),
+    /// Passes once the service prints a line to stdout or stderr matching the regular expression
+    OutputMatches(String),
+    /// Passes once the command exits successfully
+    Command {
+        /// Command to execute
+        command: String,
+        /// Arguments to pass to the command
+        #[serde(skip_serializing_if = "Option::is_none")]
+        args: Option<Vec<String>>,
+    },
+}
+
+impl ReadinessProbe {
+    /// Checks once whether the probe passes. Output probes are checked by the executor of the service
+    /// and never pass here. Commands still running at the deadline or on an interrupt do not pass.
+    pub fn passes(&self, work_dir: &Path, deadline: Instant, interrupt: &AtomicBool) -> bool {
+        match self {
+            ReadinessProbe::TcpPort(address) => tcp_port_open(address),
+            ReadinessProbe::FileExists(path) => work_dir.join(path).exists(),
+            ReadinessProbe::OutputMatches(_) => false,
+            ReadinessProbe::Command { command, args } => {
+                command_succeeds(command, args, work_dir, deadline, interrupt)
+            }
+        }
+    }
+}
+
+/// Checks whether a TCP connection to the address can be established
+pub fn tcp_port_open(address: &str) -> bool {
+    address
+        .to_socket_addrs()
+        .into_iter()
+        .flatten()
+        .any(|address| TcpStream::connect_timeout(&address, TCP_CONNECT_TIMEOUT).is_ok())
+}
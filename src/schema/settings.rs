@@ -1,7 +1,15 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-/// Represents the settings from a configuration file
-#[derive(Serialize, Deserialize, Clone)]
+/// Project settings
+#[derive(Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct Settings {
+    /// Whether to use the project file's directory as the working directory
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub project_file_as_work_dir: Option<bool>,
+    /// Template of the prefix printed in front of every output line of a task, defaults to `{command}`.
+    /// Supported placeholders are `{label}`, `{task}`, `{command}`, `{pid}`, `{elapsed}` and `{time}`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_prefix: Option<String>,
 }
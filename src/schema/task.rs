@@ -1,9 +1,78 @@
+use schemars::{JsonSchema, Schema};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 
-/// Represents a `Task` from a configuration file
-#[derive(Serialize, Deserialize, Clone)]
+use super::{builtin::Builtin, wait_for::WaitFor};
+
+/// Defines a single task
+#[derive(Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(deny_unknown_fields)]
+#[schemars(transform = require_one_operation)]
 pub struct Task {
-    pub command: String,
+    /// Description of the task shown in listings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Tags used to filter listings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    /// Short name of the task used in output prefixes and reports
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Whether to hide the task from listings, e.g. for helper tasks only used by jobs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hidden: Option<bool>,
+    /// Command to execute. Exactly one of a command or a built-in operation must be given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// Built-in operation which waits until a condition is met instead of executing a command
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wait_for: Option<WaitFor>,
+    /// Built-in file operation which is executed instead of a command. Its operands are passed as arguments
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub builtin: Option<Builtin>,
+    /// Arguments to pass to the command
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub args: Option<Vec<String>>,
+    /// Amount of arguments that must be passed to the command when calling the task from a job
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub required_call_args: Option<u8>,
+    /// Globs relative to the project directory which trigger a rerun in watch mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub watch: Option<Vec<String>>,
+    /// Globs of input files relative to the project directory. The task is skipped if neither the inputs nor
+    /// the command changed since the last successful execution and all outputs exist
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inputs: Option<Vec<String>>,
+    /// Globs of output files relative to the project directory which must exist for the task to be up to date
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outputs: Option<Vec<String>>,
+}
+
+/// Requires exactly one of `command`, `wait_for` and `builtin` in the schema of a task
+fn require_one_operation(schema: &mut Schema) {
+    schema.insert(
+        String::from("oneOf"),
+        json!([
+            { "required": ["command"] },
+            { "required": ["wait_for"] },
+            { "required": ["builtin"] }
+        ]),
+    );
+}
+
+impl Task {
+    /// Returns the command of the task or a description of its built-in operation
+    pub fn display_command(&self) -> String {
+        match (&self.command, &self.wait_for, &self.builtin) {
+            (Some(command), _, _) => command.clone(),
+            (None, Some(wait_for), _) => wait_for.describe(),
+            (None, None, Some(builtin)) => builtin.name().to_string(),
+            (None, None, None) => String::new(),
+        }
+    }
+
+    /// Checks whether the task is executed in-process instead of spawning a command
+    pub fn is_builtin(&self) -> bool {
+        self.wait_for.is_some() || self.builtin.is_some()
+    }
 }
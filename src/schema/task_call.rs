@@ -1,8 +1,44 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-/// Represents a `TaskCall` from a configuration file
-#[derive(Serialize, Deserialize, Clone)]
+use super::{condition::Condition, matrix::Matrix, service::Readiness};
+
+/// Adds a task to a job
+#[derive(Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct TaskCall {
+    /// Name of the task to be included in the job
     pub task: String,
+    /// Short name of the step used in output prefixes and reports, overrides the label of the task
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Arguments to pass to the task
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub args: Option<Vec<String>>,
+    /// Condition which must be met for the task to be executed. Otherwise the task is skipped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub when: Option<Condition>,
+    /// Executes the task once per combination of the matrix values
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matrix: Option<Matrix>,
+    /// Executes the task once per file matching a glob, or once per batch of files
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub for_each: Option<ForEach>,
+    /// Runs the task as a background service which is stopped when the job ends
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service: Option<bool>,
+    /// Readiness probe of a service which must pass before the following steps are executed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ready: Option<Readiness>,
+}
+
+/// Executes a task for all files matching a glob. The files are appended to the arguments of the task
+#[derive(Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ForEach {
+    /// Glob relative to the project directory
+    pub glob: String,
+    /// Amount of files passed to a single execution of the task. Defaults to 1
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub batch_size: Option<u32>,
 }
@@ -0,0 +1,275 @@
+use std::collections::{BTreeMap, HashSet};
+
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use serde::Serialize;
+
+use crate::{
+    prefix::PrefixTemplate,
+    util::{Console, MessageSeverity},
+};
+
+use super::{matrix::Matrix, project::Project, service::ReadinessProbe, task_call::TaskCall};
+
+/// Represents the severity of a finding of the semantic validation
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticSeverity {
+    Warning,
+    Error,
+}
+
+/// Represents a single finding of the semantic validation
+#[derive(Serialize, Clone)]
+pub struct Diagnostic {
+    pub severity: DiagnosticSeverity,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn warning(message: String) -> Diagnostic {
+        Diagnostic {
+            severity: DiagnosticSeverity::Warning,
+            message,
+        }
+    }
+
+    pub fn error(message: String) -> Diagnostic {
+        Diagnostic {
+            severity: DiagnosticSeverity::Error,
+            message,
+        }
+    }
+}
+
+impl Project {
+    /// Checks the project for errors which cannot be expressed by the json schema.
+    /// Warnings are printed, errors are printed and result in a failed check.
+    pub fn check_semantics(&self, console: Console) -> Result<()> {
+        let diagnostics = self.semantic_diagnostics();
+
+        let mut error_count = 0;
+        for diagnostic in &diagnostics {
+            match diagnostic.severity {
+                DiagnosticSeverity::Warning => {
+                    console.print_message(MessageSeverity::Warning, diagnostic.message.clone())
+                }
+                DiagnosticSeverity::Error => {
+                    error_count += 1;
+                    eprintln!("{}", console.format_error(diagnostic.message.clone()))
+                }
+            }
+        }
+
+        if error_count > 0 {
+            Err(anyhow!(format!(
+                "Project contains {} semantic error(s)",
+                error_count
+            )))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Collects all findings of the semantic validation in a deterministic order
+    pub fn semantic_diagnostics(&self) -> Vec<Diagnostic> {
+        let mut diagnostics: Vec<Diagnostic> = Vec::new();
+        let mut used_tasks: HashSet<&str> = HashSet::new();
+
+        let output_prefix = self
+            .settings
+            .as_ref()
+            .and_then(|settings| settings.output_prefix.as_ref());
+        if let Some(Err(error)) = output_prefix.map(|template| PrefixTemplate::parse(template)) {
+            diagnostics.push(Diagnostic::error(error.to_string()));
+        }
+
+        let jobs: BTreeMap<_, _> = self.jobs.iter().collect();
+        for (jobname, job) in jobs {
+            if job.tasks.is_empty() {
+                diagnostics.push(Diagnostic::error(format!(
+                    "Job \"{}\" does not contain any tasks",
+                    jobname
+                )));
+            }
+
+            let mut seen_calls: Vec<&TaskCall> = Vec::new();
+            for (index, taskcall) in job.tasks.iter().enumerate() {
+                used_tasks.insert(&taskcall.task);
+                let step = format!("step {}", index + 1);
+                self.check_taskcall(jobname, &step, taskcall, &mut diagnostics);
+
+                let for_each_glob =
+                    |call: &TaskCall| call.for_each.as_ref().map(|for_each| for_each.glob.clone());
+                if seen_calls.iter().any(|seen| {
+                    seen.task == taskcall.task
+                        && seen.args == taskcall.args
+                        && for_each_glob(seen) == for_each_glob(taskcall)
+                        && seen.when == taskcall.when
+                        && seen.matrix == taskcall.matrix
+                        && seen.label == taskcall.label
+                        && seen.service == taskcall.service
+                }) {
+                    diagnostics.push(Diagnostic::warning(format!(
+                        "Job \"{}\" calls task \"{}\" with the same arguments more than once ({})",
+                        jobname, taskcall.task, step
+                    )));
+                } else {
+                    seen_calls.push(taskcall);
+                }
+            }
+
+            for (name, steps) in job.lifecycle_steps() {
+                for (index, taskcall) in steps.iter().flatten().enumerate() {
+                    used_tasks.insert(&taskcall.task);
+                    let step = format!("step {} of {}", index + 1, name);
+                    self.check_taskcall(jobname, &step, taskcall, &mut diagnostics);
+                }
+            }
+        }
+
+        let mut tasks: Vec<_> = self.tasks.iter().collect();
+        tasks.sort_by_key(|(taskname, _)| *taskname);
+        for (taskname, task) in tasks {
+            let operations = [
+                task.command.is_some(),
+                task.wait_for.is_some(),
+                task.builtin.is_some(),
+            ];
+            if operations.into_iter().filter(|defined| *defined).count() != 1 {
+                diagnostics.push(Diagnostic::error(format!(
+                    "Task \"{}\" must define either a command or a built-in operation",
+                    taskname
+                )));
+            } else if task.wait_for.is_some()
+                && (task.args.is_some() || task.required_call_args.unwrap_or(0) > 0)
+            {
+                diagnostics.push(Diagnostic::error(format!(
+                    "Task \"{}\" waits for a condition, which does not accept arguments",
+                    taskname
+                )));
+            }
+            if task.outputs.is_some() && task.inputs.is_none() {
+                diagnostics.push(Diagnostic::warning(format!(
+                    "Task \"{}\" declares outputs without inputs, so it is never skipped",
+                    taskname
+                )));
+            }
+        }
+
+        let mut unused_tasks: Vec<&String> = self
+            .tasks
+            .keys()
+            .filter(|taskname| !used_tasks.contains(taskname.as_str()))
+            .collect();
+        unused_tasks.sort();
+        for taskname in unused_tasks {
+            diagnostics.push(Diagnostic::warning(format!(
+                "Task \"{}\" is not used by any job",
+                taskname
+            )));
+        }
+
+        diagnostics
+    }
+
+    /// Checks whether a single `TaskCall` references an existing task with the right amount of arguments
+    fn check_taskcall(
+        &self,
+        jobname: &str,
+        step: &str,
+        taskcall: &TaskCall,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        let Some(task) = self.tasks.get(&taskcall.task) else {
+            diagnostics.push(Diagnostic::error(format!(
+                "Job \"{}\" references unknown task \"{}\" ({})",
+                jobname, taskcall.task, step
+            )));
+            return;
+        };
+
+        if let Some(matrix) = &taskcall.matrix {
+            Self::check_matrix(jobname, step, taskcall, matrix, diagnostics);
+        }
+
+        if taskcall
+            .for_each
+            .as_ref()
+            .is_some_and(|for_each| for_each.batch_size == Some(0))
+        {
+            diagnostics.push(Diagnostic::error(format!(
+                "Job \"{}\" calls task \"{}\" with a batch size of 0 ({})",
+                jobname, taskcall.task, step
+            )));
+        }
+
+        if taskcall.service == Some(true) && task.is_builtin() {
+            diagnostics.push(Diagnostic::error(format!(
+                "Job \"{}\" runs the built-in task \"{}\" as a service ({})",
+                jobname, taskcall.task, step
+            )));
+        }
+        if taskcall.ready.is_some() && taskcall.service != Some(true) {
+            diagnostics.push(Diagnostic::error(format!(
+                "Job \"{}\" defines a readiness probe for task \"{}\" which is not a service ({})",
+                jobname, taskcall.task, step
+            )));
+        }
+        if let Some(ReadinessProbe::OutputMatches(pattern)) =
+            taskcall.ready.as_ref().map(|ready| &ready.probe)
+        {
+            if let Err(error) = Regex::new(pattern) {
+                diagnostics.push(Diagnostic::error(format!(
+                    "Job \"{}\" uses the invalid readiness pattern \"{}\" for task \"{}\" ({}): {}",
+                    jobname, pattern, taskcall.task, step, error
+                )));
+            }
+        }
+
+        let provided_args = taskcall.args.as_ref().map_or(0, |args| args.len());
+        if let Some(required_args) = task.required_call_args {
+            if provided_args != required_args as usize {
+                diagnostics.push(Diagnostic::error(format!(
+                    "Job \"{}\" calls task \"{}\" with {} arguments, but it requires exactly {} ({})",
+                    jobname,
+                    taskcall.task,
+                    provided_args,
+                    required_args,
+                    step
+                )));
+            }
+        }
+    }
+
+    /// Checks whether a matrix results in at least one combination and only references its own variables
+    fn check_matrix(
+        jobname: &str,
+        step: &str,
+        taskcall: &TaskCall,
+        matrix: &Matrix,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        let entries = matrix.exclude.iter().chain(matrix.include.iter()).flatten();
+        for entry in entries {
+            for variable in entry.keys() {
+                if !matrix.variables.contains_key(variable) {
+                    diagnostics.push(Diagnostic::error(format!(
+                        "Job \"{}\" uses unknown matrix variable \"{}\" in an exclude or include entry of task \"{}\" ({})",
+                        jobname,
+                        variable,
+                        taskcall.task,
+                        step
+                    )));
+                }
+            }
+        }
+
+        if matrix.combinations().is_empty() {
+            diagnostics.push(Diagnostic::error(format!(
+                "Job \"{}\" calls task \"{}\" with a matrix without any combinations ({})",
+                jobname, taskcall.task, step
+            )));
+        }
+    }
+}
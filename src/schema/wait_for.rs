@@ -0,0 +1,153 @@
+use std::{
+    os::unix::{net::UnixStream, process::CommandExt},
+    path::Path,
+    process::{Command, Stdio},
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::Duration,
+    time::Instant,
+};
+
+use anyhow::{anyhow, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::service::tcp_port_open;
+use crate::util::signal_process_group;
+
+/// Milliseconds between two checks if no interval is given
+const DEFAULT_INTERVAL_MS: u64 = 500;
+/// Seconds to wait if no timeout is given
+const DEFAULT_TIMEOUT_SECS: u64 = 60;
+/// Interval in which a running command probe is checked for completion
+const COMMAND_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Waits in-process until a condition is met
+#[derive(Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct WaitFor {
+    /// Condition to wait for
+    pub condition: WaitCondition,
+    /// Milliseconds between two checks of the condition. Defaults to 500
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval_ms: Option<u64>,
+    /// Seconds to wait before the task fails. Defaults to 60
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+}
+
+/// Condition a `WaitFor` task waits for. Relative paths are resolved against the working directory
+#[derive(Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum WaitCondition {
+    /// Met once a TCP connection to the address can be established, e.g. "127.0.0.1:5432"
+    TcpPort(String),
+    /// Met once a connection to the Unix socket can be established
+    UnixSocket(String),
+    /// Met once the file or directory exists
+    PathExists(String),
+    /// Met once the file or directory no longer exists
+    PathRemoved(String),
+    /// Met once the command exits successfully
+    Command {
+        /// Command to execute
+        command: String,
+        /// Arguments to pass to the command
+        #[serde(skip_serializing_if = "Option::is_none")]
+        args: Option<Vec<String>>,
+    },
+}
+
+impl WaitCondition {
+    /// Checks once whether the condition is met. Commands still running at the deadline or on an
+    /// interrupt are killed and count as not met.
+    fn is_met(&self, work_dir: &Path, deadline: Instant, interrupt: &AtomicBool) -> bool {
+        match self {
+            WaitCondition::TcpPort(address) => tcp_port_open(address),
+            WaitCondition::UnixSocket(path) => UnixStream::connect(work_dir.join(path)).is_ok(),
+            WaitCondition::PathExists(path) => work_dir.join(path).exists(),
+            WaitCondition::PathRemoved(path) => !work_dir.join(path).exists(),
+            WaitCondition::Command { command, args } => {
+                command_succeeds(command, args, work_dir, deadline, interrupt)
+            }
+        }
+    }
+}
+
+/// Runs the command in its own process group and checks whether it exits successfully before the deadline.
+/// Commands still running at the deadline or on an interrupt are killed.
+pub fn command_succeeds(
+    command: &str,
+    args: &Option<Vec<String>>,
+    work_dir: &Path,
+    deadline: Instant,
+    interrupt: &AtomicBool,
+) -> bool {
+    let Ok(mut child) = Command::new(command)
+        .args(args.iter().flatten())
+        .current_dir(work_dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .process_group(0)
+        .spawn()
+    else {
+        return false;
+    };
+
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return status.success(),
+            Ok(None) if Instant::now() < deadline && !interrupt.load(Ordering::Relaxed) => {
+                thread::sleep(COMMAND_POLL_INTERVAL)
+            }
+            _ => {
+                signal_process_group(child.id() as libc::pid_t, libc::SIGKILL);
+                let _ = child.wait();
+                return false;
+            }
+        }
+    }
+}
+
+impl WaitFor {
+    /// Returns a short description of the wait used as command line in the output
+    pub fn describe(&self) -> String {
+        match &self.condition {
+            WaitCondition::TcpPort(address) => format!("wait for tcp port {}", address),
+            WaitCondition::UnixSocket(path) => format!("wait for unix socket {}", path),
+            WaitCondition::PathExists(path) => format!("wait for path {} to exist", path),
+            WaitCondition::PathRemoved(path) => format!("wait for path {} to be removed", path),
+            WaitCondition::Command { command, args } => {
+                let mut command_line = command.clone();
+                for arg in args.iter().flatten() {
+                    command_line.push_str(&format!(" {}", arg));
+                }
+                format!("wait for command {} to succeed", command_line)
+            }
+        }
+    }
+
+    /// Blocks until the condition is met. Fails after the timeout or an interrupt.
+    pub fn wait(&self, work_dir: &Path, interrupt: &AtomicBool) -> Result<()> {
+        let interval = Duration::from_millis(self.interval_ms.unwrap_or(DEFAULT_INTERVAL_MS));
+        let timeout_secs = self.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS);
+        let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+
+        while !self.condition.is_met(work_dir, deadline, interrupt) {
+            if interrupt.load(Ordering::Relaxed) {
+                return Err(anyhow!("Waiting was interrupted"));
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(anyhow!(format!(
+                    "Condition not met within {} seconds",
+                    timeout_secs
+                )));
+            }
+            thread::sleep(interval.min(deadline - now));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,108 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Result};
+use sha2::{Digest, Sha256};
+
+use crate::util::expand_globs;
+
+/// Directory inside the project directory which holds the state of `setup`
+pub const STATE_DIR: &str = ".setup";
+/// File inside the state directory which stores the input hashes of tasks
+const CACHE_FILE: &str = "cache";
+
+/// TaskCache stores the input hashes of successfully executed tasks to skip tasks which are up to date.
+pub struct TaskCache {
+    project_dir: PathBuf,
+    entries: BTreeMap<String, String>,
+    force: bool,
+}
+
+impl TaskCache {
+    /// Loads the cache of the given project directory. A missing or unreadable cache file results in an empty cache.
+    /// With `force`, no task is considered up to date.
+    pub fn load(project_dir: &Path, force: bool) -> TaskCache {
+        let cache_file = project_dir.join(STATE_DIR).join(CACHE_FILE);
+        let entries = std::fs::read_to_string(cache_file)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        TaskCache {
+            project_dir: project_dir.to_path_buf(),
+            entries,
+            force,
+        }
+    }
+
+    /// Hashes the content of all input files together with the command line of the task
+    pub fn hash_inputs(&self, inputs: &[String], execution_string: &str) -> Result<String> {
+        let mut hasher = Sha256::new();
+        hasher.update(execution_string.as_bytes());
+
+        for file in expand_globs(&self.project_dir, inputs)? {
+            if !file.is_file() {
+                continue;
+            }
+            let content = match std::fs::read(&file) {
+                Ok(content) => content,
+                Err(error) => {
+                    return Err(anyhow!(format!(
+                        "Cannot read input file '{}' ({})",
+                        file.display(),
+                        error
+                    )))
+                }
+            };
+
+            let relative_path = file.strip_prefix(&self.project_dir).unwrap_or(&file);
+            hasher.update(relative_path.as_os_str().as_encoded_bytes());
+            hasher.update(b"\0");
+            hasher.update(Sha256::digest(&content));
+        }
+
+        let hash = hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        Ok(hash)
+    }
+
+    /// Checks whether a task is up to date. This is the case if the hash did not change and all outputs exist.
+    pub fn is_up_to_date(&self, key: &str, hash: &str, outputs: &[String]) -> Result<bool> {
+        if self.force || self.entries.get(key).map(String::as_str) != Some(hash) {
+            return Ok(false);
+        }
+
+        for output in outputs {
+            if expand_globs(&self.project_dir, std::slice::from_ref(output))?.is_empty() {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Stores the hash of a successfully executed task and writes the cache file
+    pub fn update(&mut self, key: &str, hash: String) -> Result<()> {
+        self.entries.insert(key.to_string(), hash);
+
+        let state_dir = self.project_dir.join(STATE_DIR);
+        let cache_file = state_dir.join(CACHE_FILE);
+        let content = serde_json::to_string_pretty(&self.entries)?;
+        let write_result =
+            std::fs::create_dir_all(&state_dir).and_then(|_| std::fs::write(&cache_file, content));
+
+        match write_result {
+            Ok(()) => Ok(()),
+            Err(error) => Err(anyhow!(format!(
+                "Cannot write file '{}' ({})",
+                cache_file.display(),
+                error
+            ))),
+        }
+    }
+}
@@ -1,24 +1,78 @@
 use anyhow::{anyhow, Result};
+use regex::Regex;
 use std::{
+    collections::VecDeque,
     io::{BufRead, BufReader},
-    os::unix::process::ExitStatusExt,
-    path::Path,
+    os::unix::process::{CommandExt, ExitStatusExt},
+    path::{Path, PathBuf},
     process::{Child, Command, ExitStatus, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
     thread::{self, JoinHandle},
+    time::{Duration, Instant, SystemTime},
 };
 
 use crate::{
-    schema::{task::Task, task_call::TaskCall},
-    util::{print_message, MessageSeverity},
+    builtin::file_actions,
+    events::{next_task_id, OutputStream},
+    output_buffer::OutputBuffer,
+    report::{unix_secs, StepPlan, TaskReport, TaskStatus},
+    reporter::{MessageLevel, Reporter, TaskInfo},
+    schema::{
+        output_mode::OutputMode,
+        service::{ReadinessProbe, DEFAULT_READY_TIMEOUT_SECS},
+        task::Task,
+        task_call::TaskCall,
+    },
+    task_cache::TaskCache,
+    util::{
+        forget_service, register_service, signal_process, signal_process_group, Console,
+        MessageSeverity,
+    },
 };
 
+/// Interval in which readiness probes and stopping services are checked
+const SERVICE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Time a service gets to stop after SIGTERM before it is killed
+const SERVICE_STOP_TIMEOUT: Duration = Duration::from_secs(5);
+/// Amount of the last stderr lines kept for reports
+const STDERR_TAIL_LINES: usize = 20;
+
 /// TaskExecutor is a struct that will be responsible for executing a single task.
 pub struct TaskExecutor {
+    pub id: usize,
+    pub taskname: String,
     pub task: Task,
+    pub taskcall: TaskCall,
     pub execution_string: String,
-    process: Command,
+    pub label: Option<String>,
+    pub output_mode: OutputMode,
+    pub parallel_index: Option<usize>,
+    /// Stops waiting built-in operations once set
+    pub interrupt: Arc<AtomicBool>,
+    process: Option<Command>,
+    args: Vec<String>,
+    /// Project file whose directory is used as working directory, if set
+    set_working_dir: Option<String>,
+    work_dir: Option<PathBuf>,
     child_process: Option<Child>,
-    silent_children: bool,
+    builtin_handle: Option<JoinHandle<Result<()>>>,
+    reporter: Arc<dyn Reporter>,
+    output_buffer: Option<Arc<Mutex<OutputBuffer>>>,
+    start_time: Option<Instant>,
+    started_at: Option<SystemTime>,
+    pid: Option<u32>,
+    duration: Option<Duration>,
+    exit_status: Option<ExitStatus>,
+    cache: Arc<Mutex<TaskCache>>,
+    input_hash: Option<String>,
+    skip_reason: Option<String>,
+    output_pattern: Option<Regex>,
+    output_matched: Arc<AtomicBool>,
+    stderr_tail: Arc<Mutex<VecDeque<String>>>,
+    stopped: bool,
     err_reader_handle: Option<JoinHandle<()>>,
     out_reader_handle: Option<JoinHandle<()>>,
 }
@@ -29,17 +83,31 @@ impl TaskExecutor {
         task: &Task,
         taskcall: &TaskCall,
         set_working_dir: &Option<String>,
-        silent_children: bool,
+        reporter: &Arc<dyn Reporter>,
+        cache: &Arc<Mutex<TaskCache>>,
     ) -> Result<TaskExecutor> {
         let mut execution_command = String::new();
 
-        // Setup initial command
-        let mut command: Command = Command::new(&task.command);
-        execution_command.push_str(&task.command);
+        // Setup initial command, built-in operations are executed in-process without a command
+        let mut command: Option<Command> = match (&task.command, task.is_builtin()) {
+            (Some(program), false) => Some(Command::new(program)),
+            (None, true) => None,
+            _ => {
+                return Err(anyhow!(format!(
+                    "Task \"{}\" must define either a command or a built-in operation",
+                    taskcall.task
+                )))
+            }
+        };
+        execution_command.push_str(&task.display_command());
 
         // Add task arguments
+        let mut all_args: Vec<String> = Vec::new();
         if let Some(args) = &task.args {
-            command.args(args);
+            all_args.extend(args.iter().cloned());
+            if let Some(command) = command.as_mut() {
+                command.args(args);
+            }
             args.iter().for_each(|arg| {
                 execution_command.push_str(format!(" {}", arg).as_str());
             });
@@ -51,7 +119,7 @@ impl TaskExecutor {
                 if args.len() != required_call_args as usize {
                     let error_message = format!(
                         "Task {} requires exactly {} additional arguments but {} were provided",
-                        task.command,
+                        task.display_command(),
                         required_call_args,
                         args.len()
                     );
@@ -59,7 +127,10 @@ impl TaskExecutor {
                 }
             }
 
-            command.args(args);
+            all_args.extend(args.iter().cloned());
+            if let Some(command) = command.as_mut() {
+                command.args(args);
+            }
             args.iter().for_each(|arg| {
                 execution_command.push_str(format!(" {}", arg).as_str());
             });
@@ -67,48 +138,323 @@ impl TaskExecutor {
             if required_call_args > 0 {
                 let error_message = format!(
                     "Task {} requires exactly {} additional arguments but 0 were provided",
-                    task.command, required_call_args,
+                    task.display_command(),
+                    required_call_args,
                 );
                 return Err(anyhow!(error_message));
             }
         }
 
         // Change working directory if needed
-        if let Some(dir) = set_working_dir {
-            let stem_path = Path::new(&dir).parent().unwrap();
-            if stem_path.is_dir() {
-                command.current_dir(stem_path);
+        let work_dir: Option<PathBuf> = set_working_dir
+            .as_ref()
+            .and_then(|dir| Path::new(dir).parent())
+            .filter(|stem_path| stem_path.is_dir())
+            .map(Path::to_path_buf);
+
+        if let Some(command) = command.as_mut() {
+            if let Some(work_dir) = &work_dir {
+                command.current_dir(work_dir);
+            }
+
+            // Services run in their own process group, so they can be stopped together with their children
+            if taskcall.service == Some(true) {
+                command.process_group(0);
             }
+
+            command.stdout(Stdio::piped());
+            command.stderr(Stdio::piped());
         }
 
-        command.stdout(Stdio::piped());
-        command.stderr(Stdio::piped());
+        let output_pattern = match taskcall.ready.as_ref().map(|ready| &ready.probe) {
+            Some(ReadinessProbe::OutputMatches(pattern)) => match Regex::new(pattern) {
+                Ok(regex) => Some(regex),
+                Err(error) => {
+                    return Err(anyhow!(format!(
+                        "Invalid readiness pattern \"{}\" ({})",
+                        pattern, error
+                    )))
+                }
+            },
+            _ => None,
+        };
 
         Ok(TaskExecutor {
+            id: next_task_id(),
+            taskname: taskcall.task.clone(),
             task: task.clone(),
+            taskcall: taskcall.clone(),
             process: command,
+            args: all_args,
+            set_working_dir: set_working_dir.clone(),
+            work_dir,
             child_process: None,
+            builtin_handle: None,
             execution_string: execution_command,
+            label: taskcall.label.clone().or_else(|| task.label.clone()),
+            output_mode: OutputMode::Interleaved,
+            parallel_index: None,
+            interrupt: Arc::default(),
             err_reader_handle: None,
             out_reader_handle: None,
-            silent_children,
+            reporter: reporter.clone(),
+            output_buffer: None,
+            start_time: None,
+            started_at: None,
+            pid: None,
+            duration: None,
+            exit_status: None,
+            cache: cache.clone(),
+            input_hash: None,
+            skip_reason: None,
+            output_pattern,
+            output_matched: Arc::new(AtomicBool::new(false)),
+            stderr_tail: Arc::new(Mutex::new(VecDeque::new())),
+            stopped: false,
         })
     }
 
+    /// Appends further arguments to the command of the task
+    pub fn append_args(&mut self, args: &[String]) {
+        if let Some(process) = self.process.as_mut() {
+            process.args(args);
+        }
+        self.args.extend(args.iter().cloned());
+        for arg in args {
+            self.execution_string.push_str(&format!(" {}", arg));
+        }
+    }
+
+    /// Checks whether the executor is the template of a `for_each` step, which is not executed itself
+    pub fn is_for_each(&self) -> bool {
+        self.taskcall.for_each.is_some()
+    }
+
+    /// Creates the executor of a `for_each` step for a batch of files, which are appended as arguments.
+    /// The condition of the step is not evaluated again.
+    pub fn for_files(&self, files: &[String]) -> Result<TaskExecutor> {
+        let mut taskcall = self.taskcall.clone();
+        taskcall.for_each = None;
+        taskcall.when = None;
+        let mut executor = TaskExecutor::new(
+            &self.task,
+            &taskcall,
+            &self.set_working_dir,
+            &self.reporter,
+            &self.cache,
+        )?;
+        executor.label = self.label.clone();
+        executor.output_mode = self.output_mode;
+        executor.interrupt = self.interrupt.clone();
+        executor.append_args(files);
+        executor.append_label(match files.len() {
+            1 => files[0].clone(),
+            count => format!("{} (+{} more)", files[0], count - 1),
+        });
+        Ok(executor)
+    }
+
+    /// Returns the working directory the task is executed in
+    pub fn work_dir(&self) -> PathBuf {
+        match &self.work_dir {
+            Some(dir) => dir.clone(),
+            None => std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+        }
+    }
+
+    /// Returns the command line, working directory and environment changes of the task
+    pub fn plan(&self) -> StepPlan {
+        let work_dir = self.work_dir().display().to_string();
+
+        let env = self
+            .process
+            .iter()
+            .flat_map(|process| process.get_envs())
+            .map(|(key, value)| match value {
+                Some(value) => format!("{}={}", key.to_string_lossy(), value.to_string_lossy()),
+                None => format!("{} (removed)", key.to_string_lossy()),
+            })
+            .collect();
+
+        StepPlan {
+            task: self.taskname.clone(),
+            label: self.label.clone(),
+            command: self.execution_string.clone(),
+            work_dir,
+            env,
+            service: self.is_service(),
+            actions: self.planned_actions(),
+        }
+    }
+
+    /// Returns the changes to the file system a built-in task would perform
+    fn planned_actions(&self) -> Vec<String> {
+        let Some(builtin) = self.task.builtin else {
+            return Vec::new();
+        };
+
+        match file_actions(builtin, &self.args, &self.work_dir()) {
+            Ok(actions) => actions.iter().map(|action| action.to_string()).collect(),
+            Err(error) => vec![format!("fail: {}", error)],
+        }
+    }
+
+    /// Prints the execution plan of the task without executing it
+    pub fn print_plan(&self, step: usize, console: Console) {
+        let plan = self.plan();
+        console.print_message(
+            MessageSeverity::Result,
+            format!("Step {}: {}", step, self.display_name()),
+        );
+        console.print_message(
+            MessageSeverity::Result,
+            format!("  working directory: {}", plan.work_dir),
+        );
+        if plan.service {
+            console.print_message(
+                MessageSeverity::Result,
+                String::from("  runs as a service until the job ends"),
+            );
+        }
+        for action in &plan.actions {
+            console.print_message(MessageSeverity::Result, format!("  would {}", action));
+        }
+        if plan.env.is_empty() {
+            console.print_message(
+                MessageSeverity::Result,
+                String::from("  environment: inherited without changes"),
+            );
+        } else {
+            console.print_message(
+                MessageSeverity::Result,
+                format!("  environment: {}", plan.env.join(", ")),
+            );
+        }
+    }
+
+    /// Returns the result of the task execution so far
+    pub fn report(&self) -> TaskReport {
+        let status = match self.exit_status {
+            _ if self.skip_reason.is_some() => TaskStatus::Skipped,
+            _ if self.stopped => TaskStatus::Success,
+            Some(status) if status.success() => TaskStatus::Success,
+            Some(_) => TaskStatus::Failed,
+            None if self.start_time.is_some() => TaskStatus::Failed,
+            None => TaskStatus::NotStarted,
+        };
+
+        TaskReport {
+            task: self.taskname.clone(),
+            label: self.label.clone(),
+            command: self.execution_string.clone(),
+            status,
+            pid: self.pid,
+            exit_code: self.exit_status.and_then(|status| status.code()),
+            signal: self.exit_status.and_then(|status| status.signal()),
+            started_at: self.started_at.map(unix_secs),
+            finished_at: self
+                .started_at
+                .zip(self.duration)
+                .map(|(started_at, duration)| unix_secs(started_at + duration)),
+            duration_secs: self.duration.map(|duration| duration.as_secs_f64()),
+            skip_reason: self.skip_reason.clone(),
+            stderr_tail: self.stderr_tail.lock().unwrap().iter().cloned().collect(),
+        }
+    }
+
+    /// Appends a further part to the label, e.g. the values of a matrix combination
+    pub fn append_label(&mut self, label: String) {
+        self.label = Some(match self.label.take() {
+            Some(existing) => format!("{}, {}", existing, label),
+            None => label,
+        });
+    }
+
+    /// Returns the command line of the task followed by its label, if any
+    pub fn display_name(&self) -> String {
+        match &self.label {
+            Some(label) => format!("{} [{}]", self.execution_string, label),
+            None => self.execution_string.clone(),
+        }
+    }
+
+    /// Skips the task instead of executing it
+    pub fn skip(&mut self, reason: &str) {
+        self.skip_reason = Some(reason.to_string());
+        self.reporter.task_skipped(&self.info(), reason);
+    }
+
+    /// Checks whether the task was skipped instead of executed
+    pub fn is_skipped(&self) -> bool {
+        self.skip_reason.is_some()
+    }
+
+    /// Returns the key of the task in the `TaskCache`
+    fn cache_key(&self) -> String {
+        format!("{}: {}", self.taskname, self.display_name())
+    }
+
+    /// Checks whether the task is up to date with its inputs and outputs. Remembers the input hash otherwise.
+    fn check_up_to_date(&mut self) -> Result<bool> {
+        let Some(inputs) = &self.task.inputs else {
+            return Ok(false);
+        };
+        let outputs = self.task.outputs.clone().unwrap_or_default();
+
+        let cache = self.cache.lock().unwrap();
+        let hash = cache.hash_inputs(inputs, &self.execution_string)?;
+        self.reporter.message(
+            MessageLevel::Debug,
+            &format!("Input hash of task \"{}\" is {}", self.display_name(), hash),
+        );
+        if cache.is_up_to_date(&self.cache_key(), &hash, &outputs)? {
+            return Ok(true);
+        }
+        drop(cache);
+
+        self.input_hash = Some(hash);
+        Ok(false)
+    }
+
     /// Executes the process and stores the child process
     pub fn execute(&mut self) -> Result<()> {
-        let child = self.process.spawn();
+        if !self.is_service() && self.check_up_to_date()? {
+            self.skip("up to date");
+            return Ok(());
+        }
+
+        // Services keep running until the end of the job, so their output is never grouped
+        if self.output_mode != OutputMode::Interleaved && !self.is_service() {
+            let buffer = OutputBuffer::new(self.id.to_string());
+            self.output_buffer = Some(Arc::new(Mutex::new(buffer)));
+        }
+
+        self.start_time = Some(Instant::now());
+        self.started_at = Some(SystemTime::now());
+        let Some(process) = self.process.as_mut() else {
+            self.execute_builtin();
+            return Ok(());
+        };
+
+        let child = process.spawn();
         match child {
             Ok(mut child) => {
-                print_message(
-                    MessageSeverity::Info,
-                    format!("Executing task \"{}\"...", self.execution_string),
+                self.pid = Some(child.id());
+                self.reporter.message(
+                    MessageLevel::Verbose,
+                    &format!(
+                        "Spawned process {} for task \"{}\" in \"{}\"",
+                        child.id(),
+                        self.display_name(),
+                        self.work_dir().display()
+                    ),
                 );
-
-                if !self.silent_children {
-                    self.bind_output(&mut child);
+                if self.is_service() {
+                    register_service(child.id() as libc::pid_t);
                 }
-
+                // Reported before the output readers start, so no output line precedes the spawn
+                self.reporter.task_started(&self.info(), self.pid);
+                self.bind_output(&mut child);
                 self.child_process = Some(child);
 
                 Ok(())
@@ -116,14 +462,80 @@ impl TaskExecutor {
             Err(error) => {
                 let error_message = format!(
                     "Cannot spawn process for task '{}' ({})",
-                    self.task.command, error
+                    self.task.display_command(),
+                    error
                 );
                 Err(anyhow!(error_message))
             }
         }
     }
 
-    /// Binds stdout and stderr to the given output and prefixes both with the given prefix.
+    /// Returns the task as seen by reporters
+    pub fn info(&self) -> TaskInfo {
+        TaskInfo {
+            id: self.id,
+            task: self.taskname.clone(),
+            label: self.label.clone(),
+            command: self.execution_string.clone(),
+            pid: self.pid,
+            start_time: self.start_time,
+            parallel_index: self.parallel_index,
+        }
+    }
+
+    /// Returns the destination of the output lines of the task
+    fn output_target(&self) -> OutputTarget {
+        OutputTarget {
+            info: self.info(),
+            reporter: self.reporter.clone(),
+            buffer: self.output_buffer.clone(),
+        }
+    }
+
+    /// Executes the built-in operation of the task in a separate thread
+    fn execute_builtin(&mut self) {
+        self.reporter.task_started(&self.info(), None);
+        let output_target = self.output_target();
+        let task = self.task.clone();
+        let args = self.args.clone();
+        let work_dir = self.work_dir();
+        let interrupt = self.interrupt.clone();
+        self.builtin_handle = Some(thread::spawn(move || {
+            if let Some(wait_for) = &task.wait_for {
+                wait_for.wait(&work_dir, &interrupt)?;
+            }
+            if let Some(builtin) = task.builtin {
+                for action in file_actions(builtin, &args, &work_dir)? {
+                    action.apply()?;
+                    output_target.line(OutputStream::Stdout, &action.to_string());
+                }
+            }
+            Ok(())
+        }));
+    }
+
+    /// Waits for the built-in operation to finish and returns an exit status reflecting its result.
+    /// Errors are printed like the error output of a spawned process.
+    fn join_builtin(&mut self, handle: JoinHandle<Result<()>>) -> ExitStatus {
+        let result = handle
+            .join()
+            .unwrap_or_else(|_| Err(anyhow!("Built-in operation panicked")));
+
+        let exitstatus = match result {
+            Ok(()) => ExitStatus::from_raw(0),
+            Err(error) => {
+                remember_stderr_line(&self.stderr_tail, &error.to_string());
+                self.output_target()
+                    .line(OutputStream::Stderr, &error.to_string());
+                ExitStatus::from_raw(1 << 8)
+            }
+        };
+        self.finish(exitstatus);
+        exitstatus
+    }
+
+    /// Reads stdout and stderr of the child and passes every line to the reporter or the output buffer.
+    /// Lines matching the readiness pattern of a service and the last lines of stderr are recorded.
     fn bind_output(&mut self, child: &mut Child) {
         let stdout = child.stdout.take().expect("Unable to take child stdout");
         let stderr = child.stderr.take().expect("Unable to take child stderr");
@@ -131,33 +543,169 @@ impl TaskExecutor {
         let out_reader = BufReader::new(stdout);
         let err_reader = BufReader::new(stderr);
 
-        let out_reader_execution_string = self.execution_string.clone();
-        self.out_reader_handle = Some(thread::spawn(move || {
-            for line in out_reader.lines().map_while(Result::ok) {
-                print_message(
-                    MessageSeverity::ChildInfo,
-                    format!("{} -> {}", out_reader_execution_string, line),
-                );
+        let output_target = self.output_target();
+        let output_reader = |stream: OutputStream| {
+            let output_target = output_target.clone();
+            let output_pattern = self.output_pattern.clone();
+            let output_matched = self.output_matched.clone();
+            move |line: String| {
+                output_target.line(stream, &line);
+                if output_pattern
+                    .as_ref()
+                    .is_some_and(|pattern| pattern.is_match(&line))
+                {
+                    output_matched.store(true, Ordering::SeqCst);
+                }
             }
+        };
+
+        let handle_out_line = output_reader(OutputStream::Stdout);
+        self.out_reader_handle = Some(thread::spawn(move || {
+            out_reader
+                .lines()
+                .map_while(Result::ok)
+                .for_each(handle_out_line);
         }));
 
-        let err_reader_execution_string = self.execution_string.clone();
+        let handle_err_line = output_reader(OutputStream::Stderr);
+        let stderr_tail = self.stderr_tail.clone();
         self.err_reader_handle = Some(thread::spawn(move || {
             for line in err_reader.lines().map_while(Result::ok) {
-                print_message(
-                    MessageSeverity::ChildError,
-                    format!("{} -> {}", err_reader_execution_string, line),
-                );
+                remember_stderr_line(&stderr_tail, &line);
+                handle_err_line(line);
             }
         }));
     }
 
+    /// Checks whether the task is executed as a background service
+    pub fn is_service(&self) -> bool {
+        self.taskcall.service == Some(true)
+    }
+
+    /// Checks whether the task is a service which was started and did not finish yet
+    pub fn is_running_service(&self) -> bool {
+        self.is_service() && self.child_process.is_some() && self.exit_status.is_none()
+    }
+
+    /// Sends SIGTERM to the process of the running command, e.g. after an interrupt
+    pub fn terminate(&mut self) {
+        if self.exit_status.is_some() {
+            return;
+        }
+        if let Some(child) = &self.child_process {
+            signal_process(child.id() as libc::pid_t, libc::SIGTERM);
+        }
+    }
+
+    /// Returns the point in time the task was started, if it was started
+    pub fn start_time(&self) -> Option<Instant> {
+        self.start_time
+    }
+
+    /// Waits until the readiness probe of the started service passes. Services without a probe are
+    /// ready immediately.
+    pub fn wait_until_ready(&mut self) -> Result<()> {
+        if let Some(ready) = self.taskcall.ready.clone() {
+            let timeout_secs = ready.timeout_secs.unwrap_or(DEFAULT_READY_TIMEOUT_SECS);
+            let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+            let work_dir = self.work_dir();
+            self.reporter.message(
+                MessageLevel::Debug,
+                &format!(
+                    "Waiting up to {}s for service \"{}\" to become ready",
+                    timeout_secs,
+                    self.display_name()
+                ),
+            );
+
+            while !(self.output_matched.load(Ordering::SeqCst)
+                || ready.probe.passes(&work_dir, deadline, &self.interrupt))
+            {
+                if self.interrupt.load(Ordering::Relaxed) {
+                    return Err(anyhow!(format!(
+                        "Waiting for service \"{}\" to become ready was interrupted",
+                        self.display_name()
+                    )));
+                }
+                if let Some(exitstatus) = self.try_wait()? {
+                    return Err(anyhow!(format!(
+                        "Service \"{}\" exited before becoming ready ({})",
+                        self.display_name(),
+                        exitstatus
+                    )));
+                }
+                if Instant::now() >= deadline {
+                    return Err(anyhow!(format!(
+                        "Service \"{}\" did not become ready within {} seconds",
+                        self.display_name(),
+                        timeout_secs
+                    )));
+                }
+                thread::sleep(SERVICE_POLL_INTERVAL);
+            }
+        }
+
+        self.reporter.service_ready(&self.info());
+        Ok(())
+    }
+
+    /// Stops the running service and its children with SIGTERM and kills them if they do not stop in time
+    pub fn stop_service(&mut self) {
+        let Some(child) = self.child_process.as_mut() else {
+            return;
+        };
+        let process_group = child.id() as libc::pid_t;
+        forget_service(process_group);
+
+        // A service which already exited on its own is not stopped, its exit status is reported as is
+        if let Ok(Some(exitstatus)) = child.try_wait() {
+            signal_process_group(process_group, libc::SIGKILL);
+            self.join_output_readers();
+            self.finish(exitstatus);
+            return;
+        }
+
+        self.reporter.service_stopping(&self.info());
+        let Some(child) = self.child_process.as_mut() else {
+            return;
+        };
+        signal_process_group(process_group, libc::SIGTERM);
+
+        let deadline = Instant::now() + SERVICE_STOP_TIMEOUT;
+        let exitstatus = loop {
+            match child.try_wait() {
+                Ok(None) if Instant::now() < deadline => thread::sleep(SERVICE_POLL_INTERVAL),
+                Ok(Some(exitstatus)) => break Some(exitstatus),
+                _ => {
+                    signal_process_group(process_group, libc::SIGKILL);
+                    break child.wait().ok();
+                }
+            }
+        };
+
+        // Remaining children of the service would keep the output open
+        signal_process_group(process_group, libc::SIGKILL);
+        self.stopped = true;
+        self.join_output_readers();
+        if let Some(exitstatus) = exitstatus {
+            self.finish(exitstatus);
+        }
+    }
+
     /// Waits for the child process to finish and returns the childs status code.
     pub fn wait(&mut self) -> Result<ExitStatus> {
+        if self.is_skipped() {
+            return Ok(ExitStatus::from_raw(0));
+        }
+        if let Some(handle) = self.builtin_handle.take() {
+            return Ok(self.join_builtin(handle));
+        }
+
         let child = self.child_process.as_mut().unwrap();
         let exitstate = child.wait()?;
-        self.out_reader_handle.take().unwrap().join().unwrap();
-        self.err_reader_handle.take().unwrap().join().unwrap();
+        forget_service(child.id() as libc::pid_t);
+        self.join_output_readers();
+        self.finish(exitstate);
 
         Ok(exitstate)
     }
@@ -165,16 +713,124 @@ impl TaskExecutor {
     /// Tries to wait for the child process to finish and returns the childs status code.
     /// This method does not actually wait for the child process to finish.
     pub fn try_wait(&mut self) -> Result<Option<ExitStatus>> {
+        if self.is_skipped() {
+            return Ok(Some(ExitStatus::from_raw(0)));
+        }
+        if let Some(handle) = self.builtin_handle.take() {
+            if !handle.is_finished() {
+                self.builtin_handle = Some(handle);
+                return Ok(None);
+            }
+            return Ok(Some(self.join_builtin(handle)));
+        }
+
         let Some(child) = self.child_process.as_mut() else {
             return Ok(Some(ExitStatus::from_raw(1)));
         };
 
         let exitstatus = child.try_wait()?;
-        if exitstatus.is_some() {
-            self.out_reader_handle.take().unwrap().join().unwrap();
-            self.err_reader_handle.take().unwrap().join().unwrap();
+        if let Some(exitstatus) = exitstatus {
+            forget_service(child.id() as libc::pid_t);
+            self.join_output_readers();
+            self.finish(exitstatus);
         }
 
         Ok(exitstatus)
     }
+
+    /// Waits for the output reader threads to print all remaining output
+    fn join_output_readers(&mut self) {
+        if let Some(handle) = self.out_reader_handle.take() {
+            handle.join().unwrap();
+        }
+        if let Some(handle) = self.err_reader_handle.take() {
+            handle.join().unwrap();
+        }
+    }
+
+    /// Reports the buffered output of the task as one block. With `grouped-on-failure`, only the output of
+    /// failed tasks is reported.
+    fn print_grouped_output(&mut self, exitstatus: ExitStatus) {
+        let Some(buffer) = self.output_buffer.take() else {
+            return;
+        };
+        if self.output_mode == OutputMode::GroupedOnFailure && exitstatus.success() {
+            return;
+        }
+
+        let info = self.info();
+        let result = buffer
+            .lock()
+            .unwrap()
+            .drain(|stream, line| self.reporter.output_line(&info, stream, line));
+        if let Err(error) = result {
+            self.reporter.message(
+                MessageLevel::Warning,
+                &format!(
+                    "Cannot use the temporary output file of task \"{}\" ({})",
+                    self.display_name(),
+                    error
+                ),
+            );
+        }
+    }
+
+    /// Stores the exit status and duration of the finished child process
+    fn finish(&mut self, exitstatus: ExitStatus) {
+        self.exit_status = Some(exitstatus);
+        self.duration = self.start_time.map(|start_time| start_time.elapsed());
+        self.reporter.message(
+            MessageLevel::Verbose,
+            &format!(
+                "Task \"{}\" finished with {} after {:.2}s",
+                self.display_name(),
+                exitstatus,
+                self.duration.unwrap_or_default().as_secs_f64()
+            ),
+        );
+
+        self.print_grouped_output(exitstatus);
+        self.reporter.task_finished(&self.info(), &self.report());
+
+        // Remember the inputs of successful tasks to skip them next time
+        if let Some(hash) = self.input_hash.take() {
+            if exitstatus.success() {
+                let cache_key = self.cache_key();
+                if let Err(error) = self.cache.lock().unwrap().update(&cache_key, hash) {
+                    self.reporter
+                        .message(MessageLevel::Warning, &error.to_string());
+                }
+            }
+        }
+    }
+}
+
+/// Destination of the output lines of a task, the buffer of grouped output or directly the reporter
+#[derive(Clone)]
+struct OutputTarget {
+    info: TaskInfo,
+    reporter: Arc<dyn Reporter>,
+    buffer: Option<Arc<Mutex<OutputBuffer>>>,
+}
+
+impl OutputTarget {
+    /// Passes the line on. Lines which cannot be buffered are reported immediately.
+    fn line(&self, stream: OutputStream, line: &str) {
+        let buffered = self
+            .buffer
+            .as_ref()
+            .is_some_and(|buffer| buffer.lock().unwrap().push(stream, line).is_ok());
+        if !buffered {
+            self.reporter.output_line(&self.info, stream, line);
+        }
+    }
+}
+
+/// Remembers a line of stderr, dropping the oldest line once `STDERR_TAIL_LINES` are kept
+fn remember_stderr_line(stderr_tail: &Mutex<VecDeque<String>>, line: &str) {
+    let mut stderr_tail = stderr_tail.lock().unwrap();
+    if stderr_tail.len() == STDERR_TAIL_LINES {
+        stderr_tail.pop_front();
+    }
+    stderr_tail.push_back(line.to_string());
 }
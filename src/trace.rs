@@ -0,0 +1,131 @@
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::report::{JobReport, TaskReport};
+
+/// Process id used for all events, the lanes are distinguished by their thread id
+const TRACE_PID: u32 = 1;
+/// Lane of the event spanning the whole job
+const JOB_LANE: usize = 0;
+
+/// Single event of the Chrome Trace Event Format
+#[derive(Serialize)]
+struct TraceEvent {
+    name: String,
+    ph: &'static str,
+    pid: u32,
+    tid: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cat: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ts: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dur: Option<f64>,
+    args: BTreeMap<&'static str, Value>,
+}
+
+impl TraceEvent {
+    /// Creates a metadata event naming a process or thread
+    fn metadata(name: &str, tid: usize, value: String) -> TraceEvent {
+        TraceEvent {
+            name: name.to_string(),
+            ph: "M",
+            pid: TRACE_PID,
+            tid,
+            cat: None,
+            ts: None,
+            dur: None,
+            args: BTreeMap::from([("name", json!(value))]),
+        }
+    }
+}
+
+/// Writes the execution of the job as Chrome Trace Event Format, viewable in Perfetto or chrome://tracing.
+/// Every task is placed in the first lane which is free at its start, so parallel tasks get separate lanes.
+pub fn write_trace(file: &str, report: &JobReport) -> Result<()> {
+    let hook_tasks = report.hooks.iter().flat_map(|hook| hook.tasks.iter());
+    let mut tasks: Vec<&TaskReport> = report
+        .tasks
+        .iter()
+        .chain(hook_tasks)
+        .filter(|task| task.started_at.is_some() && task.finished_at.is_some())
+        .collect();
+    tasks.sort_by(|a, b| a.started_at.partial_cmp(&b.started_at).unwrap());
+
+    let mut events: Vec<TraceEvent> = vec![TraceEvent::metadata(
+        "process_name",
+        JOB_LANE,
+        format!("setup run {}", report.job),
+    )];
+    events.push(TraceEvent::metadata(
+        "thread_name",
+        JOB_LANE,
+        String::from("job"),
+    ));
+
+    // Timestamps are given in microseconds since the start of the job
+    let to_timestamp = |unix_secs: f64| (unix_secs - report.started_at) * 1_000_000.0;
+    let job_end = to_timestamp(report.finished_at);
+    events.push(TraceEvent {
+        name: report.job.clone(),
+        ph: "X",
+        pid: TRACE_PID,
+        tid: JOB_LANE,
+        cat: Some("job"),
+        ts: Some(0.0),
+        dur: Some(job_end),
+        args: BTreeMap::from([("success", json!(report.success))]),
+    });
+
+    let mut lane_ends: Vec<f64> = Vec::new();
+    for task in tasks {
+        let start = to_timestamp(task.started_at.unwrap());
+        let end = to_timestamp(task.finished_at.unwrap());
+        let lane = match lane_ends.iter().position(|lane_end| *lane_end <= start) {
+            Some(lane) => lane,
+            None => {
+                lane_ends.push(end);
+                events.push(TraceEvent::metadata(
+                    "thread_name",
+                    lane_ends.len(),
+                    format!("slot {}", lane_ends.len()),
+                ));
+                lane_ends.len() - 1
+            }
+        };
+        lane_ends[lane] = end;
+
+        events.push(TraceEvent {
+            name: match &task.label {
+                Some(label) => format!("{} [{}]", task.task, label),
+                None => task.task.clone(),
+            },
+            ph: "X",
+            pid: TRACE_PID,
+            tid: lane + 1,
+            cat: Some("task"),
+            ts: Some(start),
+            dur: Some(end - start),
+            args: BTreeMap::from([
+                ("command", json!(task.command)),
+                ("status", json!(task.status)),
+                ("exit_code", json!(task.exit_code)),
+                ("signal", json!(task.signal)),
+                ("pid", json!(task.pid)),
+            ]),
+        });
+    }
+
+    let trace = json!({ "traceEvents": events, "displayTimeUnit": "ms" });
+    let content = serde_json::to_string_pretty(&trace)?;
+    match std::fs::write(file, content) {
+        Ok(()) => Ok(()),
+        Err(error) => Err(anyhow!(format!(
+            "Cannot write trace file \"{}\" ({})",
+            file, error
+        ))),
+    }
+}
@@ -1,10 +1,23 @@
 use anyhow::Result;
-use std::{path::Path, str::FromStr};
+use std::{
+    io::IsTerminal,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, LazyLock, Mutex,
+    },
+};
 use termion::color;
 
+use crate::cli::ColorMode;
+
 use serde_json::Value;
 
-/// Imports the json content of a projectfile
+/// File names which are checked when auto detecting a project file
+pub const PROJECT_FILE_NAMES: [&str; 3] = ["Setup.json", "Setup.yaml", "Setup.yml"];
+
+/// Imports the json content of a projectfile. Files with a `.yaml` or `.yml` extension are parsed as YAML.
 pub fn import_project_value(projectfile: &str) -> Result<Value> {
     let project_data = std::fs::read_to_string(projectfile);
     let project_data = match project_data {
@@ -16,84 +29,356 @@ pub fn import_project_value(projectfile: &str) -> Result<Value> {
         }
     };
 
-    let value = Value::from_str(&project_data);
+    parse_project_data(&project_data, is_yaml_file(projectfile), projectfile)
+}
+
+/// Parses the content of a project file as YAML or JSON. The `source` names the content in error messages.
+pub fn parse_project_data(project_data: &str, yaml: bool, source: &str) -> Result<Value> {
+    if yaml {
+        return match serde_yaml::from_str::<Value>(project_data) {
+            Ok(value) => Ok(value),
+            Err(error) => Err(anyhow::anyhow!(format!(
+                "Cannot parse YAML '{source}' ({error})"
+            ))),
+        };
+    }
+
+    let value = Value::from_str(project_data);
     match value {
         Ok(value) => Ok(value),
         Err(error) => Err(anyhow::anyhow!(format!(
-            "Cannot parse JSON '{projectfile}' ({error})"
+            "Cannot parse JSON '{source}' ({error})"
+        ))),
+    }
+}
+
+/// Returns the directory of the project file
+pub fn project_dir(projectfile: &str) -> Result<PathBuf> {
+    let dir = match Path::new(projectfile).parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+
+    match dir.canonicalize() {
+        Ok(dir) => Ok(dir),
+        Err(error) => Err(anyhow::anyhow!(format!(
+            "Cannot resolve project directory '{}' ({})",
+            dir.display(),
+            error
         ))),
     }
 }
 
+/// Returns all existing paths matching the globs in a sorted order. Globs are relative to the base directory.
+/// Fails if a path matching a glob cannot be read.
+pub fn expand_globs(base_dir: &Path, globs: &[String]) -> Result<Vec<PathBuf>> {
+    // Special characters in the base directory are matched literally
+    let escaped_base_dir = glob::Pattern::escape(&base_dir.to_string_lossy());
+    let mut paths: Vec<PathBuf> = Vec::new();
+    for pattern in globs {
+        let full_pattern = match Path::new(pattern).is_absolute() {
+            true => pattern.clone(),
+            false => format!("{}/{}", escaped_base_dir.trim_end_matches('/'), pattern),
+        };
+        let matches = match glob::glob(&full_pattern) {
+            Ok(matches) => matches,
+            Err(error) => {
+                return Err(anyhow::anyhow!(format!(
+                    "Invalid glob \"{}\" ({})",
+                    pattern, error
+                )))
+            }
+        };
+        for path in matches {
+            match path {
+                Ok(path) => paths.push(path),
+                Err(error) => {
+                    return Err(anyhow::anyhow!(format!(
+                        "Cannot read path '{}' matching glob \"{}\" ({})",
+                        error.path().display(),
+                        pattern,
+                        error.error()
+                    )))
+                }
+            }
+        }
+    }
+
+    paths.sort();
+    paths.dedup();
+    Ok(paths)
+}
+
+/// Checks whether the given file is a YAML file based on its extension
+pub fn is_yaml_file(file: &str) -> bool {
+    matches!(
+        Path::new(file).extension().and_then(|extension| extension.to_str()),
+        Some("yaml") | Some("yml")
+    )
+}
+
 /// Tries to auto detect the project file
-pub fn detect_project_file() -> Result<String> {
-    print_message(
+pub fn detect_project_file(console: Console) -> Result<String> {
+    console.print_message(
         MessageSeverity::Info,
         String::from("Trying to auto detect project file..."),
     );
 
-    let mut path_to_check = String::from("Setup.json");
+    let mut directory_to_check = String::new();
 
     for _ in 0..25 {
-        let path = Path::new(&path_to_check);
-        if path.exists() {
-            print_message(
-                MessageSeverity::Success,
-                format!("Detected project file \"{}\"", path.display()),
-            );
-            return Ok(path_to_check);
-        } else {
-            path_to_check = format!("../{}", path_to_check);
+        for file_name in PROJECT_FILE_NAMES {
+            let path_to_check = format!("{}{}", directory_to_check, file_name);
+            let path = Path::new(&path_to_check);
+            if path.exists() {
+                console.print_message(
+                    MessageSeverity::Success,
+                    format!("Detected project file \"{}\"", path.display()),
+                );
+                return Ok(path_to_check);
+            }
         }
+
+        directory_to_check = format!("../{}", directory_to_check);
     }
 
     Err(anyhow::anyhow!("Could not auto detect project file"))
 }
 
-/// Formats an error message with color
-pub fn format_error(error: String) -> String {
-    format!(
-        "{}[ERROR]  :{} {}",
-        color::Fg(color::Red),
-        color::Fg(color::Reset),
-        error
-    )
+/// Checks whether the given tags contain the tag to filter by. Returns true if no filter is set.
+pub fn has_tag(tags: &Option<Vec<String>>, tag: &Option<String>) -> bool {
+    match tag {
+        Some(tag) => tags.as_ref().is_some_and(|tags| tags.contains(tag)),
+        None => true,
+    }
+}
+
+/// Formats rows as a table with aligned columns. The header is used as the first row.
+pub fn format_table(header: &[&str], rows: &[Vec<String>]) -> Vec<String> {
+    let mut widths: Vec<usize> = header.iter().map(|column| column.chars().count()).collect();
+    for row in rows {
+        for (index, cell) in row.iter().enumerate() {
+            widths[index] = widths[index].max(cell.chars().count());
+        }
+    }
+
+    let format_row = |cells: Vec<&str>| -> String {
+        cells
+            .iter()
+            .enumerate()
+            .map(|(index, cell)| format!("{:width$}", cell, width = widths[index]))
+            .collect::<Vec<String>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut lines = vec![format_row(header.to_vec())];
+    for row in rows {
+        lines.push(format_row(row.iter().map(String::as_str).collect()));
+    }
+    lines
+}
+
+/// Whether an interrupt (Ctrl+C) was received by the command line interface
+static INTERRUPT_RECEIVED: LazyLock<Arc<AtomicBool>> = LazyLock::new(Arc::default);
+
+/// Whether a second interrupt (Ctrl+C) was received by the command line interface, which also stops hooks
+static HOOK_INTERRUPT_RECEIVED: LazyLock<Arc<AtomicBool>> = LazyLock::new(Arc::default);
+
+/// Records that an interrupt (Ctrl+C) was received. Called by the ctrl+c handler.
+/// The first interrupt stops the job, a second one also stops its hooks.
+pub fn register_interrupt() {
+    if INTERRUPT_RECEIVED.swap(true, Ordering::Relaxed) {
+        HOOK_INTERRUPT_RECEIVED.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Forgets previously received interrupts, called when a run of the command line interface starts
+pub fn reset_interrupt() {
+    INTERRUPT_RECEIVED.store(false, Ordering::Relaxed);
+    HOOK_INTERRUPT_RECEIVED.store(false, Ordering::Relaxed);
+}
+
+/// Returns the interrupt flag of the command line interface, which is set by `register_interrupt`
+pub fn cli_interrupt() -> Arc<AtomicBool> {
+    INTERRUPT_RECEIVED.clone()
+}
+
+/// Returns the flag of the command line interface which stops hooks, set by a second `register_interrupt`
+pub fn cli_hook_interrupt() -> Arc<AtomicBool> {
+    HOOK_INTERRUPT_RECEIVED.clone()
+}
+
+/// Process groups of the services which are currently running
+static RUNNING_SERVICES: Mutex<Vec<libc::pid_t>> = Mutex::new(Vec::new());
+
+/// Remembers the process group of a started service, so it can be stopped if the process exits early
+pub fn register_service(process_group: libc::pid_t) {
+    if let Ok(mut services) = RUNNING_SERVICES.lock() {
+        services.push(process_group);
+    }
+}
+
+/// Forgets the process group of a service which exited or was stopped
+pub fn forget_service(process_group: libc::pid_t) {
+    if let Ok(mut services) = RUNNING_SERVICES.lock() {
+        services.retain(|service| *service != process_group);
+    }
+}
+
+/// Sends SIGTERM to all running services. Called before the process exits without stopping them regularly.
+pub fn terminate_services() {
+    if let Ok(mut services) = RUNNING_SERVICES.lock() {
+        for process_group in services.drain(..) {
+            signal_process_group(process_group, libc::SIGTERM);
+        }
+    }
+}
+
+/// Sends the signal to a single process.
+pub fn signal_process(pid: libc::pid_t, signal: libc::c_int) -> bool {
+    // SAFETY: `kill` has no memory safety requirements
+    unsafe { libc::kill(pid, signal) == 0 }
+}
+
+/// Sends the signal to all processes of the process group. Returns whether any process received it.
+pub fn signal_process_group(process_group: libc::pid_t, signal: libc::c_int) -> bool {
+    // SAFETY: `kill` has no memory safety requirements, a negative pid addresses the process group
+    unsafe { libc::kill(-process_group, signal) == 0 }
+}
+
+/// Checks whether an interrupt (Ctrl+C) was received by the command line interface
+pub fn interrupt_received() -> bool {
+    INTERRUPT_RECEIVED.load(Ordering::Relaxed)
+}
+
+/// Amount of messages printed by `print_message`
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Verbosity {
+    /// Only results, warnings, errors and the output of child processes
+    Quiet,
+    #[default]
+    Normal,
+    /// Additionally prints details about the execution
+    Verbose,
+    /// Additionally prints debug details
+    Debug,
+}
+
+/// Settings of the messages printed to the console. Kept per run, so runs with different settings do not
+/// influence each other. The default prints normal messages without colors.
+#[derive(Clone, Copy, Default)]
+pub struct Console {
+    /// Print all messages to stderr instead of stdout, e.g. to keep stdout free for json output
+    pub messages_to_stderr: bool,
+    pub verbosity: Verbosity,
+    /// Whether lines written to stdout are colored
+    pub color_stdout: bool,
+    /// Whether lines written to stderr are colored
+    pub color_stderr: bool,
 }
 
-/// Prints a formatted and colored line to the console
-pub fn print_message(severity: MessageSeverity, message: String) {
-    match severity {
-        MessageSeverity::Info => println!(
-            "{}[INFO]    :{} {}",
-            color::Fg(color::Blue),
-            color::Fg(color::Reset),
-            message
-        ),
-        MessageSeverity::Success => println!(
-            "{}[SUCCESS] :{} {}",
-            color::Fg(color::Green),
-            color::Fg(color::Reset),
-            message
-        ),
-        MessageSeverity::ChildInfo => println!(
-            "{}[CHILDI]  :{} {}",
-            color::Fg(color::Yellow),
-            color::Fg(color::Reset),
-            message
-        ),
-        MessageSeverity::ChildError => println!(
-            "{}[CHILDERR]:{} {}",
-            color::Fg(color::Red),
-            color::Fg(color::Reset),
-            message
-        ),
+impl Console {
+    /// Decides for stdout and stderr separately whether to color them. With `auto`, only terminals are colored
+    /// and `NO_COLOR` disables colors.
+    pub fn with_color_mode(self, mode: ColorMode) -> Console {
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        let (color_stdout, color_stderr) = match mode {
+            ColorMode::Always => (true, true),
+            ColorMode::Never => (false, false),
+            ColorMode::Auto if no_color => (false, false),
+            ColorMode::Auto => (
+                std::io::stdout().is_terminal(),
+                std::io::stderr().is_terminal(),
+            ),
+        };
+        Console {
+            color_stdout,
+            color_stderr,
+            ..self
+        }
+    }
+
+    /// Prints a formatted and colored line to the console. Diagnostics of `setup` itself are printed to stderr.
+    pub fn print_message(&self, severity: MessageSeverity, message: String) {
+        if !self.visible(severity) {
+            return;
+        }
+
+        let (label, color): (&str, &dyn color::Color) = match severity {
+            MessageSeverity::Info => ("[INFO]    :", &color::Blue),
+            MessageSeverity::Success => ("[SUCCESS] :", &color::Green),
+            MessageSeverity::Warning => ("[WARNING] :", &color::Yellow),
+            MessageSeverity::Verbose => ("[VERBOSE] :", &color::Cyan),
+            MessageSeverity::Debug => ("[DEBUG]   :", &color::LightBlack),
+            MessageSeverity::Result => ("[INFO]    :", &color::Blue),
+            MessageSeverity::ChildInfo => ("[CHILDI]  :", &color::Yellow),
+            MessageSeverity::ChildError => ("[CHILDERR]:", &color::Red),
+        };
+        let to_stderr = self.uses_stderr(severity);
+        let line = format!("{} {}", self.paint(label, color, to_stderr), message);
+
+        if to_stderr {
+            eprintln!("{}", line);
+        } else {
+            println!("{}", line);
+        }
+    }
+
+    /// Formats an error message, colored if stderr is colored
+    pub fn format_error(&self, error: String) -> String {
+        format!("{} {}", self.paint("[ERROR]  :", &color::Red, true), error)
+    }
+
+    /// Colors the text if the stream it is written to is colored
+    pub fn paint(&self, text: &str, color: &dyn color::Color, stderr: bool) -> String {
+        let colored = if stderr {
+            self.color_stderr
+        } else {
+            self.color_stdout
+        };
+        if colored {
+            format!("{}{}{}", color::Fg(color), text, color::Fg(color::Reset))
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// Checks whether messages of the severity are printed to stderr. Only results and the output of child
+    /// processes are printed to stdout.
+    pub fn uses_stderr(&self, severity: MessageSeverity) -> bool {
+        match severity {
+            MessageSeverity::Result | MessageSeverity::ChildInfo | MessageSeverity::ChildError => {
+                self.messages_to_stderr
+            }
+            _ => true,
+        }
+    }
+
+    /// Checks whether messages of the severity are printed at the `Verbosity` of the console
+    fn visible(&self, severity: MessageSeverity) -> bool {
+        match severity {
+            MessageSeverity::Info | MessageSeverity::Success => self.verbosity >= Verbosity::Normal,
+            MessageSeverity::Verbose => self.verbosity >= Verbosity::Verbose,
+            MessageSeverity::Debug => self.verbosity >= Verbosity::Debug,
+            _ => true,
+        }
     }
 }
 
 /// Represents the severity of a message written to the console
+#[derive(Clone, Copy)]
 pub enum MessageSeverity {
     Info,
     Success,
+    Warning,
+    /// Details only printed with `--verbose`
+    Verbose,
+    /// Details only printed with `-vv`
+    Debug,
+    /// Results of a mode like listings and plans, printed to stdout even with `--quiet`
+    Result,
     ChildInfo,
     ChildError,
 }
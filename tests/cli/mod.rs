@@ -0,0 +1,179 @@
+use std::process::{Command, Output, Stdio};
+
+use clap::Parser;
+use setup::{
+    cli::{CliParameters, ColorMode, Mode},
+    run, Console,
+};
+
+#[test]
+/// Testing that the output arguments are passed on to nested runs
+fn output_args_test() {
+    let args = CliParameters::try_parse_from([
+        "setup",
+        "--silent-children",
+        "true",
+        "run",
+        "job",
+        "-vv",
+        "--color",
+        "never",
+    ])
+    .unwrap();
+    assert_eq!(args.verbose, 2);
+    assert!(args.color == Some(ColorMode::Never));
+    assert_eq!(
+        args.output_args(),
+        ["--silent-children", "true", "-vv", "--color", "never"]
+    );
+}
+
+#[test]
+/// Testing that quiet and verbose output cannot be combined
+fn quiet_verbose_conflict_test() {
+    assert!(CliParameters::try_parse_from(["setup", "-q", "-v", "validate"]).is_err());
+}
+
+#[test]
+/// Testing a quiet run without colors
+fn quiet_run_test() {
+    let cli_args = CliParameters {
+        projectfile: Some(String::from("./tests/execution/json/simple_project.json")),
+        silent_children: None,
+        output: None,
+        quiet: true,
+        verbose: 0,
+        color: Some(ColorMode::Never),
+        mode: Mode::Run {
+            job: String::from("job1"),
+            dry_run: false,
+            force: false,
+            trace: None,
+            junit: None,
+            events: None,
+            output_mode: None,
+        },
+    };
+
+    assert!(run(cli_args).is_ok());
+}
+
+/// Runs the `setup` binary on the simple project, with stdout and stderr piped
+fn run_simple_project(args: &[&str], envs: &[(&str, &str)]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_setup"))
+        .arg("./tests/execution/json/simple_project.json")
+        .args(args)
+        .env_remove("NO_COLOR")
+        .envs(envs.iter().copied())
+        .output()
+        .unwrap()
+}
+
+#[test]
+/// Testing that diagnostics are printed to stderr and suppressed by `--quiet`, while results and the output of
+/// the tasks stay on stdout
+fn message_streams_test() {
+    let output = run_simple_project(&["run", "job1"], &[]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success());
+    assert!(stdout.contains("first") && stdout.contains("second"));
+    assert!(!stdout.contains("Executing"));
+    assert!(stderr.contains("Executing job \"job1\""));
+
+    let output = run_simple_project(&["-q", "run", "job1"], &[]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("first") && stdout.contains("second"));
+    assert!(output.stderr.is_empty());
+
+    let output = run_simple_project(&["-q", "list-jobs"], &[]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("job1"));
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+/// Testing that only `--color always` colors output which is not written to a terminal
+fn color_mode_test() {
+    let colored = |output: Output| {
+        [output.stdout, output.stderr]
+            .iter()
+            .any(|stream| String::from_utf8_lossy(stream).contains('\x1b'))
+    };
+    assert!(colored(run_simple_project(
+        &["--color", "always", "run", "job1"],
+        &[]
+    )));
+    assert!(!colored(run_simple_project(&["run", "job1"], &[])));
+    assert!(!colored(run_simple_project(
+        &["--color", "never", "run", "job1"],
+        &[]
+    )));
+    assert!(!colored(run_simple_project(
+        &["--color", "auto", "run", "job1"],
+        &[("NO_COLOR", "1")]
+    )));
+    assert_eq!(
+        Console::default().paint("text", &termion::color::Red, false),
+        "text"
+    );
+}
+
+#[test]
+/// Testing that the output prefixes of a parallel `for_each` step are padded to the longest label from the
+/// first line on
+fn prefix_width_test() {
+    let dir = std::env::temp_dir().join(format!("setup_prefix_width_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("a.txt"), "").unwrap();
+    std::fs::write(dir.join("a_much_longer_name.txt"), "").unwrap();
+    std::fs::write(
+        dir.join("Setup.json"),
+        r#"{
+            "settings": { "project_file_as_work_dir": true, "output_prefix": "{label}" },
+            "jobs": {
+                "job": {
+                    "tasks": [
+                        { "task": "echo", "args": ["short"], "label": "x" },
+                        { "task": "echo", "for_each": { "glob": "*.txt" } }
+                    ],
+                    "parallel": true
+                }
+            },
+            "tasks": { "echo": { "command": "echo" } }
+        }"#,
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_setup"))
+        .arg(dir.join("Setup.json"))
+        .args(["--color", "never", "run", "job"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let columns: Vec<usize> = stdout
+        .lines()
+        .filter_map(|line| line.find(" -> "))
+        .collect();
+    assert_eq!(columns.len(), 3);
+    assert!(columns.iter().all(|column| *column == columns[0]));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+/// Testing that printing the json schema to a closed pipe does not fail
+fn schema_closed_pipe_test() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_setup"))
+        .arg("schema")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    drop(child.stdout.take());
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+}
@@ -0,0 +1,100 @@
+use setup::{
+    cli::{CliParameters, CompletionShell, Mode, NameKind},
+    run,
+};
+
+#[test]
+/// Testing the generation of completion scripts for all supported shells
+fn completions_test() {
+    for shell in [
+        CompletionShell::Bash,
+        CompletionShell::Zsh,
+        CompletionShell::Fish,
+    ] {
+        let cli_args = CliParameters {
+            projectfile: None,
+            silent_children: None,
+            output: None,
+            quiet: false,
+            verbose: 0,
+            color: None,
+            mode: Mode::Completions { shell },
+        };
+
+        let completions_result = run(cli_args);
+        assert!(completions_result.is_ok())
+    }
+}
+
+#[test]
+/// Testing the listing of job names used by the completion scripts
+fn complete_names_test() {
+    let cli_args = CliParameters {
+        projectfile: Some(String::from("./tests/execution/json/simple_project.json")),
+        silent_children: None,
+        output: None,
+        quiet: false,
+        verbose: 0,
+        color: None,
+        mode: Mode::CompleteNames {
+            kind: NameKind::Jobs,
+        },
+    };
+
+    let names_result = run(cli_args);
+    assert!(names_result.is_ok())
+}
+
+#[test]
+/// Testing that job names are completed for `run` and `watch` in the bash and fish scripts
+fn watch_job_completion_test() {
+    let script = |shell: &str| {
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_setup"))
+            .args(["completions", shell])
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    assert!(script("bash").contains("run|watch) [[ -z \"${kind}\" ]] && kind=\"jobs\""));
+    assert!(script("fish")
+        .contains("__fish_seen_subcommand_from run watch\" -f -a \"(setup complete-names jobs"));
+}
+
+#[test]
+/// Testing that the bash script skips option values and completes the job names of an explicit project file
+fn bash_dynamic_completion_test() {
+    let executable = std::path::Path::new(env!("CARGO_BIN_EXE_setup"));
+    let path = format!(
+        "{}:{}",
+        executable.parent().unwrap().display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+    let complete = |words: &str| {
+        let output = std::process::Command::new("bash")
+            .arg("-c")
+            .arg(format!(
+                "source <(setup completions bash)
+                COMP_WORDS=({} '')
+                COMP_CWORD=$(( ${{#COMP_WORDS[@]}} - 1 ))
+                _setup_dynamic
+                printf '%s\\n' \"${{COMPREPLY[@]}}\"",
+                words
+            ))
+            .env("PATH", path.clone())
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let project_file = "./tests/execution/json/simple_project.json";
+    assert_eq!(complete(&format!("setup {} run", project_file)), "job1\n");
+    assert_eq!(
+        complete(&format!("setup --output json {} run", project_file)),
+        "job1\n"
+    );
+    assert_eq!(
+        complete(&format!("setup {} run --trace trace.json", project_file)),
+        "job1\n"
+    );
+}
@@ -0,0 +1,1194 @@
+use std::{
+    io::{BufRead, BufReader},
+    process::{Command, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use setup::{
+    cli::{CliParameters, Mode, OutputFormat},
+    report::{HookReport, JobReport, TaskReport, TaskStatus},
+    run, JobOptions, LoadedProject,
+};
+
+use crate::json_output;
+
+static JSON_BASE_PATH: &str = "./tests/execution/json/";
+
+#[test]
+/// Testing a dry run of an existing job
+fn dry_run_job_test() {
+    let project_file_path = format!("{}{}", JSON_BASE_PATH, "simple_project.json");
+
+    let cli_args = CliParameters {
+        projectfile: Some(project_file_path),
+        silent_children: None,
+        output: None,
+        quiet: false,
+        verbose: 0,
+        color: None,
+        mode: Mode::Run {
+            job: String::from("job1"),
+            dry_run: true,
+            force: false,
+            trace: None,
+            junit: None,
+            events: None,
+            output_mode: None,
+        },
+    };
+
+    let execution_result = run(cli_args);
+    assert!(execution_result.is_ok())
+}
+
+#[test]
+/// Testing the json plan of a dry run
+fn dry_run_job_json_test() {
+    let project_file_path = format!("{}{}", JSON_BASE_PATH, "simple_project.json");
+
+    let plan = json_output(&project_file_path, &["run", "job1", "--dry-run"]);
+    assert_eq!(plan["job"], "job1");
+    assert_eq!(plan["parallel"], true);
+    let commands: Vec<&str> = plan["steps"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|step| step["command"].as_str().unwrap())
+        .collect();
+    assert_eq!(commands, vec!["echo first", "echo second"]);
+    assert_eq!(plan["hooks"], serde_json::json!([]));
+}
+
+#[test]
+/// Testing that a dry run prints the steps in the order of their execution and reports warnings
+fn dry_run_step_order_test() {
+    let dir = std::env::temp_dir().join(format!("setup_dry_run_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("Setup.json"),
+        r#"{
+            "settings": { "project_file_as_work_dir": true },
+            "jobs": {
+                "job1": {
+                    "before": [{ "task": "echo" }],
+                    "tasks": [{ "task": "echo", "for_each": { "glob": "*.nothing" } }],
+                    "after": [{ "task": "echo" }],
+                    "finally": [{ "task": "echo" }]
+                }
+            },
+            "tasks": { "echo": { "command": "echo" } }
+        }"#,
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_setup"))
+        .arg(dir.join("Setup.json"))
+        .args(["run", "job1", "--dry-run"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let headers: Vec<&str> = stdout
+        .lines()
+        .filter(|line| line.contains(" would execute "))
+        .collect();
+    assert_eq!(headers.len(), 4);
+    assert!(headers[0].contains("Steps \"before\""));
+    assert!(headers[1].contains("Job \"job1\""));
+    assert!(headers[2].contains("Steps \"after\""));
+    assert!(headers[3].contains("Steps \"finally\""));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No files match \"*.nothing\""));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+/// Testing a dry run of a job which does not exist
+fn dry_run_unknown_job_test() {
+    let project_file_path = format!("{}{}", JSON_BASE_PATH, "simple_project.json");
+
+    let cli_args = CliParameters {
+        projectfile: Some(project_file_path),
+        silent_children: None,
+        output: None,
+        quiet: false,
+        verbose: 0,
+        color: None,
+        mode: Mode::Run {
+            job: String::from("unknown"),
+            dry_run: true,
+            force: false,
+            trace: None,
+            junit: None,
+            events: None,
+            output_mode: None,
+        },
+    };
+
+    let execution_result = run(cli_args);
+    assert!(execution_result.is_err())
+}
+
+#[test]
+/// Testing that a job which cannot be started is reported as json error object
+fn unknown_job_json_error_test() {
+    let project_file_path = format!("{}{}", JSON_BASE_PATH, "simple_project.json");
+
+    let result = json_output(&project_file_path, &["run", "unknown"]);
+    assert_eq!(result["success"], false);
+    assert_eq!(result["error"], "Job with name \"unknown\" not found");
+
+    let result = json_output(&project_file_path, &["run-task", "echo"]);
+    assert_eq!(result["success"], false);
+}
+
+#[test]
+/// Testing a dry run of a task with missing arguments
+fn dry_run_task_missing_arguments_test() {
+    let project_file_path = format!("{}{}", JSON_BASE_PATH, "simple_project.json");
+
+    let cli_args = CliParameters {
+        projectfile: Some(project_file_path),
+        silent_children: None,
+        output: None,
+        quiet: false,
+        verbose: 0,
+        color: None,
+        mode: Mode::RunTask {
+            task: String::from("echo"),
+            arguments: vec![],
+            dry_run: true,
+            force: false,
+            watch: false,
+            clear: false,
+        },
+    };
+
+    let execution_result = run(cli_args);
+    assert!(execution_result.is_err())
+}
+
+#[test]
+/// Testing the execution of a job with json output
+fn run_job_json_output_test() {
+    let project_file_path = format!("{}{}", JSON_BASE_PATH, "simple_project.json");
+
+    let cli_args = CliParameters {
+        projectfile: Some(project_file_path),
+        silent_children: None,
+        output: Some(OutputFormat::Json),
+        quiet: false,
+        verbose: 0,
+        color: None,
+        mode: Mode::Run {
+            job: String::from("job1"),
+            dry_run: false,
+            force: false,
+            trace: None,
+            junit: None,
+            events: None,
+            output_mode: None,
+        },
+    };
+
+    let execution_result = run(cli_args);
+    assert!(execution_result.is_ok())
+}
+
+#[test]
+/// Testing watch mode with a job which does not exist
+fn watch_unknown_job_test() {
+    let project_file_path = format!("{}{}", JSON_BASE_PATH, "simple_project.json");
+
+    let cli_args = CliParameters {
+        projectfile: Some(project_file_path),
+        silent_children: None,
+        output: None,
+        quiet: false,
+        verbose: 0,
+        color: None,
+        mode: Mode::Watch {
+            job: String::from("unknown"),
+            clear: false,
+        },
+    };
+
+    let execution_result = run(cli_args);
+    assert!(execution_result.is_err())
+}
+
+#[test]
+/// Testing that changed files trigger a rerun in watch mode, while changes in ignored directories do not
+fn watch_rerun_test() {
+    let dir = std::env::temp_dir().join(format!("setup_watch_rerun_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("src")).unwrap();
+    std::fs::create_dir_all(dir.join("target")).unwrap();
+    std::fs::write(
+        dir.join("Setup.json"),
+        r#"{
+            "settings": { "project_file_as_work_dir": true },
+            "jobs": {
+                "job": {
+                    "tasks": [ { "task": "log" } ],
+                    "watch": ["**/*.txt"],
+                    "before": [ { "task": "prepare" } ],
+                    "finally": [ { "task": "prepare", "for_each": { "glob": "*.cfg" } } ]
+                }
+            },
+            "tasks": {
+                "log": { "command": "sh", "args": ["-c", "echo run >> runs.log"] },
+                "prepare": { "command": "true", "watch": ["src/*.md"] }
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let mut watch = Command::new(env!("CARGO_BIN_EXE_setup"))
+        .arg(dir.join("Setup.json"))
+        .args(["watch", "job"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let stderr = watch.stderr.take().unwrap();
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+            if line.contains("Detected change in") && sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    // Waits until the job was run the given number of times
+    let runs = |count: usize| {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while Instant::now() < deadline {
+            let log = std::fs::read_to_string(dir.join("runs.log")).unwrap_or_default();
+            if log.lines().count() >= count {
+                return log.lines().count();
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        0
+    };
+    // Waits for the next change detected by the watcher
+    let detected_change = || {
+        receiver
+            .recv_timeout(Duration::from_secs(10))
+            .unwrap_or_default()
+    };
+    assert_eq!(runs(1), 1);
+
+    // The change of a watched file is only detected after the creation of the ignored directory
+    std::fs::create_dir_all(dir.join("node_modules")).unwrap();
+    std::fs::write(dir.join("src").join("a.txt"), "changed").unwrap();
+    assert!(detected_change().contains("a.txt"));
+    assert_eq!(runs(2), 2);
+
+    // Existing and newly created ignored directories are not watched, so only the following change is detected
+    std::fs::write(dir.join("target").join("a.txt"), "changed").unwrap();
+    std::fs::write(dir.join("node_modules").join("a.txt"), "changed").unwrap();
+    std::fs::write(dir.join("src").join("b.txt"), "changed").unwrap();
+    assert!(detected_change().contains("b.txt"));
+    assert_eq!(runs(3), 3);
+
+    // Globs of the tasks of lifecycle steps and of `for_each` steps are watched as well
+    std::fs::write(dir.join("src").join("README.md"), "changed").unwrap();
+    assert!(detected_change().contains("README.md"));
+    assert_eq!(runs(4), 4);
+    std::fs::write(dir.join("settings.cfg"), "changed").unwrap();
+    assert!(detected_change().contains("settings.cfg"));
+    assert_eq!(runs(5), 5);
+
+    let _ = watch.kill();
+    let _ = watch.wait();
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+/// Testing that tasks with unchanged inputs are skipped unless forced. The glob characters in the name of the
+/// project directory are matched literally.
+fn up_to_date_task_test() {
+    let dir = std::env::temp_dir().join(format!("setup_up_to_date_[{}]", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("in.txt"), "input").unwrap();
+    std::fs::write(
+        dir.join("Setup.json"),
+        r#"{
+            "settings": { "project_file_as_work_dir": true },
+            "jobs": { "job1": { "tasks": [ { "task": "append" } ] } },
+            "tasks": {
+                "append": {
+                    "command": "sh",
+                    "args": ["-c", "echo run >> log.txt"],
+                    "inputs": ["in.txt"],
+                    "outputs": ["log.txt"]
+                }
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let run_job = |force: bool| {
+        let cli_args = CliParameters {
+            projectfile: Some(dir.join("Setup.json").display().to_string()),
+            silent_children: None,
+            output: None,
+            quiet: false,
+            verbose: 0,
+            color: None,
+            mode: Mode::Run {
+                job: String::from("job1"),
+                dry_run: false,
+                force,
+                trace: None,
+                junit: None,
+                events: None,
+                output_mode: None,
+            },
+        };
+        assert!(run(cli_args).is_ok());
+        std::fs::read_to_string(dir.join("log.txt"))
+            .unwrap()
+            .lines()
+            .count()
+    };
+
+    assert_eq!(run_job(false), 1);
+    assert_eq!(run_job(false), 1);
+    assert_eq!(run_job(true), 2);
+
+    std::fs::write(dir.join("in.txt"), "changed input").unwrap();
+    assert_eq!(run_job(false), 3);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+/// Testing that steps whose conditions are not met are skipped
+fn skipped_conditional_steps_test() {
+    let project_file_path = format!("{}{}", JSON_BASE_PATH, "conditional_steps.json");
+
+    let cli_args = CliParameters {
+        projectfile: Some(project_file_path),
+        silent_children: None,
+        output: None,
+        quiet: false,
+        verbose: 0,
+        color: None,
+        mode: Mode::Run {
+            job: String::from("skipped_steps"),
+            dry_run: false,
+            force: false,
+            trace: None,
+            junit: None,
+            events: None,
+            output_mode: None,
+        },
+    };
+
+    let execution_result = run(cli_args);
+    assert!(execution_result.is_ok())
+}
+
+#[test]
+/// Testing that steps whose conditions are met are executed
+fn executed_conditional_step_test() {
+    let project_file_path = format!("{}{}", JSON_BASE_PATH, "conditional_steps.json");
+
+    let cli_args = CliParameters {
+        projectfile: Some(project_file_path),
+        silent_children: None,
+        output: None,
+        quiet: false,
+        verbose: 0,
+        color: None,
+        mode: Mode::Run {
+            job: String::from("executed_step"),
+            dry_run: false,
+            force: false,
+            trace: None,
+            junit: None,
+            events: None,
+            output_mode: None,
+        },
+    };
+
+    let execution_result = run(cli_args);
+    assert!(execution_result.is_err())
+}
+
+#[test]
+/// Testing the execution of a step expanded by a matrix
+fn matrix_job_test() {
+    let project_file_path = format!("{}{}", JSON_BASE_PATH, "matrix_project.json");
+
+    let cli_args = CliParameters {
+        projectfile: Some(project_file_path),
+        silent_children: None,
+        output: None,
+        quiet: false,
+        verbose: 0,
+        color: None,
+        mode: Mode::Run {
+            job: String::from("matrix_job"),
+            dry_run: false,
+            force: false,
+            trace: None,
+            junit: None,
+            events: None,
+            output_mode: None,
+        },
+    };
+
+    let execution_result = run(cli_args);
+    assert!(execution_result.is_ok())
+}
+
+#[test]
+/// Testing the combinations of a matrix in the json report
+fn matrix_job_json_test() {
+    let project_file_path = format!("{}{}", JSON_BASE_PATH, "matrix_project.json");
+
+    let report = json_output(&project_file_path, &["run", "matrix_job"]);
+    assert_eq!(report["success"], true);
+    let tasks = report["tasks"].as_array().unwrap();
+    let labels: Vec<&str> = tasks
+        .iter()
+        .map(|task| task["label"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        labels,
+        vec![
+            "feature=default, profile=debug",
+            "feature=default, profile=release",
+            "feature=full, profile=release",
+            "feature=minimal, profile=release",
+        ]
+    );
+    assert_eq!(tasks[3]["command"], "echo --profile=release minimal");
+}
+
+#[test]
+/// Testing the execution of steps fanned out over files matching a glob when the step starts
+fn for_each_job_test() {
+    let dir = std::env::temp_dir().join(format!("setup_for_each_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    for file in ["a.txt", "b.txt", "c.txt"] {
+        std::fs::write(dir.join(file), file).unwrap();
+    }
+    std::fs::write(
+        dir.join("Setup.json"),
+        r#"{
+            "settings": { "project_file_as_work_dir": true },
+            "jobs": {
+                "for_each_job": {
+                    "tasks": [
+                        { "task": "list", "for_each": { "glob": "*.txt" } },
+                        { "task": "list", "for_each": { "glob": "*.txt", "batch_size": 2 } },
+                        { "task": "list", "for_each": { "glob": "*.nothing" } },
+                        { "task": "generate" },
+                        { "task": "list", "for_each": { "glob": "*.gen" } }
+                    ]
+                },
+                "failing_job": {
+                    "tasks": [ { "task": "check", "for_each": { "glob": "*.txt" } } ]
+                },
+                "invalid_glob_job": {
+                    "tasks": [
+                        { "task": "list" },
+                        { "task": "list", "for_each": { "glob": "[" } }
+                    ],
+                    "parallel": true
+                }
+            },
+            "tasks": {
+                "list": { "command": "ls" },
+                "generate": { "command": "touch", "args": ["d.gen"] },
+                "check": { "command": "sh", "args": ["-c", "test $0 != b.txt"] }
+            }
+        }"#,
+    )
+    .unwrap();
+    let project = LoadedProject::from_path(dir.join("Setup.json")).unwrap();
+    let labels = |report: &JobReport| -> Vec<String> {
+        report
+            .tasks
+            .iter()
+            .map(|task| task.label.clone().unwrap_or_default())
+            .collect()
+    };
+
+    // The generated file did not exist when the job was started
+    let report = project
+        .execute_job("for_each_job", &JobOptions::default())
+        .unwrap();
+    assert!(report.success);
+    assert_eq!(
+        labels(&report),
+        [
+            "a.txt",
+            "b.txt",
+            "c.txt",
+            "a.txt (+1 more)",
+            "c.txt",
+            "",
+            "d.gen"
+        ]
+    );
+
+    // All files are checked, even after one of them failed
+    let report = project
+        .execute_job("failing_job", &JobOptions::default())
+        .unwrap();
+    assert_eq!(labels(&report), ["a.txt", "b.txt", "c.txt"]);
+    let statuses: Vec<String> = report
+        .tasks
+        .iter()
+        .map(|task| task.status.to_string())
+        .collect();
+    assert_eq!(statuses, ["success", "failed", "success"]);
+    assert_eq!(
+        report.error.as_deref(),
+        Some("1 of 3 runs of task \"check\" failed")
+    );
+
+    // No step of a parallel job is lost from the report if a glob cannot be expanded
+    let report = project
+        .execute_job("invalid_glob_job", &JobOptions::default())
+        .unwrap();
+    assert!(!report.success);
+    assert_eq!(report.tasks.len(), 2);
+    assert!(report
+        .tasks
+        .iter()
+        .all(|task| task.status == TaskStatus::NotStarted));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+/// Testing that the hooks matching the outcome of a job are executed
+fn job_hooks_test() {
+    let dir = std::env::temp_dir().join(format!("setup_job_hooks_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("Setup.json"),
+        r#"{
+            "settings": { "project_file_as_work_dir": true },
+            "jobs": {
+                "failing_job": {
+                    "before": [ { "task": "log", "args": ["before"] } ],
+                    "tasks": [ { "task": "fail" }, { "task": "log", "args": ["task"] } ],
+                    "after": [ { "task": "log", "args": ["after"] } ],
+                    "on_success": [ { "task": "log", "args": ["on_success"] } ],
+                    "on_failure": [ { "task": "log", "args": ["on_failure"] } ],
+                    "finally": [ { "task": "fail" }, { "task": "log", "args": ["finally"] } ]
+                },
+                "succeeding_job": {
+                    "tasks": [ { "task": "log", "args": ["task"] } ],
+                    "on_success": [ { "task": "fail" } ],
+                    "on_failure": [ { "task": "log", "args": ["on_failure"] } ]
+                }
+            },
+            "tasks": {
+                "log": {
+                    "command": "sh",
+                    "args": ["-c", "echo $0 >> log.txt"],
+                    "required_call_args": 1
+                },
+                "fail": { "command": "false" }
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let run_job = |job: &str| {
+        let cli_args = CliParameters {
+            projectfile: Some(dir.join("Setup.json").display().to_string()),
+            silent_children: None,
+            output: None,
+            quiet: false,
+            verbose: 0,
+            color: None,
+            mode: Mode::Run {
+                job: String::from(job),
+                dry_run: false,
+                force: false,
+                trace: None,
+                junit: None,
+                events: None,
+                output_mode: None,
+            },
+        };
+        let result = run(cli_args);
+        let log = std::fs::read_to_string(dir.join("log.txt")).unwrap();
+        let _ = std::fs::remove_file(dir.join("log.txt"));
+        (result.is_ok(), log)
+    };
+
+    // The failing task skips the remaining tasks and `after`, a failing hook step stops the hook
+    assert_eq!(
+        run_job("failing_job"),
+        (false, String::from("before\non_failure\n"))
+    );
+    // Hook failures do not change the outcome of the job
+    assert_eq!(run_job("succeeding_job"), (true, String::from("task\n")));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+/// Testing that services are started, awaited and stopped when the job ends
+fn service_job_test() {
+    let dir = std::env::temp_dir().join(format!("setup_service_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("Setup.json"),
+        r#"{
+            "settings": { "project_file_as_work_dir": true },
+            "jobs": {
+                "service_job": {
+                    "tasks": [
+                        {
+                            "task": "service",
+                            "service": true,
+                            "ready": { "probe": { "file_exists": "ready" }, "timeout_secs": 10 }
+                        },
+                        { "task": "check" }
+                    ]
+                },
+                "crashing_service_job": {
+                    "tasks": [
+                        {
+                            "task": "crash",
+                            "service": true,
+                            "ready": { "probe": { "output_matches": "^never$" } }
+                        },
+                        { "task": "check" }
+                    ]
+                },
+                "slow_probe_job": {
+                    "tasks": [
+                        {
+                            "task": "service",
+                            "service": true,
+                            "ready": {
+                                "probe": { "command": { "command": "sleep", "args": ["30"] } },
+                                "timeout_secs": 1
+                            }
+                        }
+                    ]
+                },
+                "never_ready_job": {
+                    "tasks": [
+                        {
+                            "task": "service",
+                            "service": true,
+                            "ready": { "probe": { "file_exists": "never" }, "timeout_secs": 30 }
+                        }
+                    ]
+                },
+                "exiting_service_job": {
+                    "tasks": [
+                        {
+                            "task": "exiting",
+                            "service": true,
+                            "ready": { "probe": { "file_exists": "ready" }, "timeout_secs": 10 }
+                        },
+                        { "task": "pause" }
+                    ]
+                }
+            },
+            "tasks": {
+                "service": { "command": "sh", "args": ["-c", "touch ready; sleep 60"] },
+                "exiting": { "command": "sh", "args": ["-c", "touch ready; exit 3"] },
+                "pause": { "command": "sleep", "args": ["1"] },
+                "crash": { "command": "false" },
+                "check": { "command": "test", "args": ["-f", "ready"] }
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let run_job = |job: &str| {
+        let cli_args = CliParameters {
+            projectfile: Some(dir.join("Setup.json").display().to_string()),
+            silent_children: None,
+            output: None,
+            quiet: false,
+            verbose: 0,
+            color: None,
+            mode: Mode::Run {
+                job: String::from(job),
+                dry_run: false,
+                force: false,
+                trace: None,
+                junit: None,
+                events: None,
+                output_mode: None,
+            },
+        };
+        run(cli_args).is_ok()
+    };
+
+    let start_time = std::time::Instant::now();
+    assert!(run_job("service_job"));
+    assert!(start_time.elapsed().as_secs() < 30);
+    assert!(!run_job("crashing_service_job"));
+
+    // Only services which were still running count as successfully stopped
+    let project = LoadedProject::from_path(dir.join("Setup.json")).unwrap();
+    let service_status = |job: &str| {
+        let report = project.execute_job(job, &JobOptions::default()).unwrap();
+        report.tasks[0].status.to_string()
+    };
+    assert_eq!(service_status("service_job"), "success");
+    assert_eq!(service_status("exiting_service_job"), "failed");
+
+    // A command probe which does not exit is killed at the timeout
+    let start_time = Instant::now();
+    let report = project
+        .execute_job("slow_probe_job", &JobOptions::default())
+        .unwrap();
+    assert!(!report.success);
+    assert!(start_time.elapsed().as_secs() < 10);
+
+    // An interrupt stops waiting for the service to become ready
+    let interrupt = Arc::new(AtomicBool::new(false));
+    let flag = interrupt.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(300));
+        flag.store(true, Ordering::Relaxed);
+    });
+    let start_time = Instant::now();
+    let report = project
+        .execute_job(
+            "never_ready_job",
+            &JobOptions::default().interrupt(interrupt),
+        )
+        .unwrap();
+    assert!(!report.success);
+    assert!(start_time.elapsed().as_secs() < 10);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+/// Testing built-in wait tasks which succeed or time out
+fn wait_for_job_test() {
+    let project_file_path = format!("{}{}", JSON_BASE_PATH, "wait_for_project.json");
+
+    let run_job = |job: &str| {
+        let cli_args = CliParameters {
+            projectfile: Some(project_file_path.clone()),
+            silent_children: None,
+            output: None,
+            quiet: false,
+            verbose: 0,
+            color: None,
+            mode: Mode::Run {
+                job: String::from(job),
+                dry_run: false,
+                force: false,
+                trace: None,
+                junit: None,
+                events: None,
+                output_mode: None,
+            },
+        };
+        run(cli_args).is_ok()
+    };
+
+    assert!(run_job("wait_job"));
+    assert!(!run_job("timeout_job"));
+
+    // A command which does not exit is killed at the deadline
+    let start_time = std::time::Instant::now();
+    assert!(!run_job("hanging_command_job"));
+    assert!(start_time.elapsed().as_secs() < 10);
+}
+
+#[test]
+/// Testing the built-in file operations
+fn builtin_file_operations_test() {
+    let dir = std::env::temp_dir().join(format!("setup_builtin_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("src/sub")).unwrap();
+    std::fs::write(dir.join("src/a.txt"), "a").unwrap();
+    std::fs::write(dir.join("src/b.log"), "b").unwrap();
+    std::fs::write(dir.join("src/sub/c.txt"), "c").unwrap();
+    std::os::unix::fs::symlink("..", dir.join("src/sub/up")).unwrap();
+    std::fs::write(
+        dir.join("Setup.json"),
+        r#"{
+            "settings": { "project_file_as_work_dir": true },
+            "jobs": {
+                "build": {
+                    "tasks": [
+                        { "task": "mkdir", "args": ["dist/empty"] },
+                        { "task": "copy", "args": ["src/*.txt", "dist/"] },
+                        { "task": "copy", "args": ["src", "dist/tree"] },
+                        { "task": "link" },
+                        { "task": "write" },
+                        { "task": "remove", "args": ["dist/tree/*.log"] }
+                    ]
+                },
+                "failing_copy": { "tasks": [{ "task": "copy", "args": ["missing/*", "dist/"] }] },
+                "recursive_copy": { "tasks": [{ "task": "copy", "args": ["src", "src/sub/backup"] }] },
+                "relink": { "tasks": [{ "task": "link_over_file" }] },
+                "clean": { "tasks": [{ "task": "remove", "args": ["dist/**/*"] }] }
+            },
+            "tasks": {
+                "mkdir": { "builtin": "mkdir", "required_call_args": 1 },
+                "copy": { "builtin": "copy", "required_call_args": 2 },
+                "link": { "builtin": "symlink", "args": ["tree", "dist/current"] },
+                "write": { "builtin": "write_file", "args": ["dist/VERSION", "1.0"] },
+                "link_over_file": { "builtin": "symlink", "args": ["a.txt", "dist/VERSION"] },
+                "remove": { "builtin": "remove", "required_call_args": 1 }
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let run_job = |job: &str| {
+        let cli_args = CliParameters {
+            projectfile: Some(dir.join("Setup.json").display().to_string()),
+            silent_children: None,
+            output: None,
+            quiet: false,
+            verbose: 0,
+            color: None,
+            mode: Mode::Run {
+                job: String::from(job),
+                dry_run: false,
+                force: false,
+                trace: None,
+                junit: None,
+                events: None,
+                output_mode: None,
+            },
+        };
+        run(cli_args).is_ok()
+    };
+
+    assert!(run_job("build"));
+    let dist = dir.join("dist");
+    assert!(dist.join("empty").is_dir());
+    assert!(dist.join("a.txt").is_file());
+    assert!(!dist.join("b.log").exists());
+    assert!(dist.join("tree/sub/c.txt").is_file());
+    assert!(!dist.join("tree/b.log").exists());
+    assert!(dist.join("current/a.txt").is_file());
+    assert_eq!(
+        std::fs::read_to_string(dist.join("VERSION")).unwrap(),
+        "1.0"
+    );
+    assert!(!run_job("failing_copy"));
+
+    // Copying a directory into itself would never end
+    assert!(!run_job("recursive_copy"));
+    assert!(!dir.join("src/sub/backup").exists());
+
+    // Existing files are replaced by the link
+    assert!(run_job("relink"));
+    assert!(dist.join("VERSION").is_symlink());
+
+    // Links are copied as links instead of following them
+    assert!(dist.join("tree/sub/up").is_symlink());
+
+    // Matches below an already removed directory are ignored
+    assert!(run_job("clean"));
+    assert!(!dist.join("tree").exists() && !dist.join("a.txt").exists());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+/// Testing the Chrome trace export of a parallel job
+fn trace_export_test() {
+    let project_file_path = format!("{}{}", JSON_BASE_PATH, "matrix_project.json");
+    let trace_file = std::env::temp_dir().join(format!("setup_trace_{}.json", std::process::id()));
+
+    let cli_args = CliParameters {
+        projectfile: Some(project_file_path),
+        silent_children: None,
+        output: None,
+        quiet: false,
+        verbose: 0,
+        color: None,
+        mode: Mode::Run {
+            job: String::from("matrix_job"),
+            dry_run: false,
+            force: false,
+            trace: Some(trace_file.display().to_string()),
+            junit: None,
+            events: None,
+            output_mode: None,
+        },
+    };
+    assert!(run(cli_args).is_ok());
+
+    let trace: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&trace_file).unwrap()).unwrap();
+    let task_events: Vec<&serde_json::Value> = trace["traceEvents"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|event| event["cat"] == "task")
+        .collect();
+    assert_eq!(task_events.len(), 4);
+    for event in task_events {
+        assert_eq!(event["ph"], "X");
+        assert_eq!(event["args"]["exit_code"], 0);
+        assert!(event["args"]["pid"].is_u64());
+        assert!(event["tid"].as_u64().unwrap() >= 1);
+    }
+
+    let _ = std::fs::remove_file(&trace_file);
+}
+
+#[test]
+/// Testing the JUnit XML report of a failing job
+fn junit_report_test() {
+    let dir = std::env::temp_dir().join(format!("setup_junit_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("Setup.json"),
+        r#"{
+            "jobs": {
+                "tests": {
+                    "tasks": [
+                        { "task": "succeed" },
+                        { "task": "succeed", "when": { "file_exists": "setup_junit_missing_file" } },
+                        { "task": "fail" },
+                        { "task": "succeed" }
+                    ]
+                }
+            },
+            "tasks": {
+                "succeed": { "command": "true" },
+                "fail": { "command": "sh", "args": ["-c", "echo 'broken <test>' >&2; exit 3"] }
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let junit_file = dir.join("junit.xml");
+    let cli_args = CliParameters {
+        projectfile: Some(dir.join("Setup.json").display().to_string()),
+        silent_children: Some(true),
+        output: None,
+        quiet: false,
+        verbose: 0,
+        color: None,
+        mode: Mode::Run {
+            job: String::from("tests"),
+            dry_run: false,
+            force: false,
+            trace: None,
+            junit: Some(junit_file.display().to_string()),
+            events: None,
+            output_mode: None,
+        },
+    };
+    assert!(run(cli_args).is_err());
+
+    let junit = std::fs::read_to_string(&junit_file).unwrap();
+    assert!(
+        junit.contains(r#"<testsuite name="tests" tests="4" failures="1" errors="0" skipped="2""#)
+    );
+    assert!(junit.contains(
+        r#"<failure message="Exit code 3" type="failure">broken &lt;test&gt;</failure>"#
+    ));
+    assert!(junit.contains(r#"<skipped message="condition not met"/>"#));
+    assert!(junit.contains(r#"<skipped message="not started"/>"#));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+/// Testing that a report file which cannot be written does not fail the job or prevent the other report
+fn report_file_error_test() {
+    let dir = std::env::temp_dir().join(format!("setup_report_error_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("Setup.json"),
+        r#"{
+            "jobs": { "tests": { "tasks": [{ "task": "succeed" }] } },
+            "tasks": { "succeed": { "command": "true" } }
+        }"#,
+    )
+    .unwrap();
+
+    let project = LoadedProject::from_path(dir.join("Setup.json")).unwrap();
+    let junit_file = dir.join("junit.xml");
+    let options = JobOptions::default()
+        .trace(dir.join("missing/trace.json").display().to_string())
+        .junit(junit_file.display().to_string());
+    let report = project.execute_job("tests", &options).unwrap();
+    assert!(report.success);
+    assert!(junit_file.is_file());
+
+    let options = JobOptions::default().junit(dir.join("missing/junit.xml").display().to_string());
+    assert!(project.execute_job("tests", &options).unwrap().success);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+/// Testing the JSON Lines event stream of a job
+fn events_stream_test() {
+    let dir = std::env::temp_dir().join(format!("setup_events_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("Setup.json"),
+        r#"{
+            "jobs": { "events_job": { "tasks": [{ "task": "greet" }] } },
+            "tasks": { "greet": { "command": "echo", "args": ["hello events"] } }
+        }"#,
+    )
+    .unwrap();
+
+    let events_file = dir.join("events.jsonl");
+    let cli_args = CliParameters {
+        projectfile: Some(dir.join("Setup.json").display().to_string()),
+        silent_children: None,
+        output: None,
+        quiet: false,
+        verbose: 0,
+        color: None,
+        mode: Mode::Run {
+            job: String::from("events_job"),
+            dry_run: false,
+            force: false,
+            trace: None,
+            junit: None,
+            events: Some(events_file.display().to_string()),
+            output_mode: None,
+        },
+    };
+    assert!(run(cli_args).is_ok());
+
+    let events: Vec<serde_json::Value> = std::fs::read_to_string(&events_file)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert!(events.iter().all(|event| event["version"] == 1));
+
+    let position =
+        |predicate: &dyn Fn(&serde_json::Value) -> bool| events.iter().position(predicate).unwrap();
+    let started =
+        position(&|event| event["event"] == "job_started" && event["job"] == "events_job");
+    let spawned = position(&|event| event["event"] == "task_spawned" && event["task"] == "greet");
+    let task_id = events[spawned]["task_id"].clone();
+    assert!(events[spawned]["pid"].is_u64());
+    let output = position(&|event| {
+        event["event"] == "output_line"
+            && event["task_id"] == task_id
+            && event["stream"] == "stdout"
+    });
+    assert_eq!(events[output]["line"], "hello events");
+    let exited = position(&|event| event["event"] == "task_exited" && event["task_id"] == task_id);
+    assert_eq!(events[exited]["status"], "success");
+    let finished =
+        position(&|event| event["event"] == "job_finished" && event["job"] == "events_job");
+    assert!(started < spawned && spawned < output && output < exited && exited < finished);
+
+    // A number is used as an open file descriptor
+    let fd_events_file = dir.join("fd_events.jsonl");
+    let status = Command::new("sh")
+        .args(["-c", "\"$0\" \"$1\" run events_job --events 3 3>\"$2\""])
+        .arg(env!("CARGO_BIN_EXE_setup"))
+        .arg(dir.join("Setup.json"))
+        .arg(&fd_events_file)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .unwrap();
+    assert!(status.success());
+    assert!(std::fs::read_to_string(&fd_events_file)
+        .unwrap()
+        .contains(r#""event":"job_finished""#));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+/// Testing the summary table with exit codes, signals, skip reasons, hooks and the summed task time
+fn summary_table_test() {
+    let task = |name: &str, status: TaskStatus, duration_secs: Option<f64>| TaskReport {
+        task: String::from(name),
+        label: None,
+        command: String::from(name),
+        status,
+        pid: None,
+        exit_code: None,
+        signal: None,
+        started_at: None,
+        finished_at: None,
+        duration_secs,
+        skip_reason: None,
+        stderr_tail: Vec::new(),
+    };
+    let report = JobReport {
+        job: String::from("ci"),
+        parallel: true,
+        success: false,
+        error: None,
+        started_at: 0.0,
+        finished_at: 1.5,
+        duration_secs: 1.5,
+        tasks: vec![
+            TaskReport {
+                label: Some(String::from("main.c")),
+                exit_code: Some(0),
+                ..task("build", TaskStatus::Success, Some(1.25))
+            },
+            TaskReport {
+                signal: Some(15),
+                ..task("server", TaskStatus::Failed, Some(0.5))
+            },
+            TaskReport {
+                skip_reason: Some(String::from("up to date")),
+                ..task("lint", TaskStatus::Skipped, None)
+            },
+        ],
+        hooks: vec![HookReport {
+            hook: String::from("finally"),
+            success: true,
+            tasks: vec![TaskReport {
+                exit_code: Some(0),
+                ..task("cleanup", TaskStatus::Success, Some(0.25))
+            }],
+        }],
+    };
+
+    assert_eq!(
+        report.summary_lines(),
+        [
+            "Summary of job \"ci\"",
+            "  TASK               LABEL   STATUS   EXIT       DURATION  SKIPPED REASON",
+            "  build              main.c  success  0          1.25s",
+            "  server                     failed   signal 15  0.50s",
+            "  lint                       skipped  -                    up to date",
+            "  cleanup (finally)          success  0          0.25s",
+            "Total wall time: 1.50s, summed task time: 2.00s",
+        ]
+    );
+}
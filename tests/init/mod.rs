@@ -0,0 +1,74 @@
+use std::path::PathBuf;
+
+use setup::{
+    cli::{CliParameters, Mode, ProjectFormat},
+    run,
+};
+
+/// Creates an empty temporary directory for a test
+fn create_test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("setup_init_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Builds the cli parameters for init mode
+fn init_args(projectfile: &str, format: ProjectFormat, force: bool) -> CliParameters {
+    CliParameters {
+        projectfile: Some(projectfile.to_string()),
+        silent_children: None,
+        output: None,
+        quiet: false,
+        verbose: 0,
+        color: None,
+        mode: Mode::Init {
+            format,
+            detect: true,
+            force,
+        },
+    }
+}
+
+#[test]
+/// Testing that init mode creates a valid project file with detected tasks
+fn init_detect_test() {
+    let dir = create_test_dir("detect");
+    std::fs::write(dir.join("Cargo.toml"), "").unwrap();
+    let project_file_path = dir.join("Setup.yaml").display().to_string();
+
+    let init_result = run(init_args(&project_file_path, ProjectFormat::Yaml, false));
+    assert!(init_result.is_ok());
+
+    let content = std::fs::read_to_string(&project_file_path).unwrap();
+    assert!(content.contains("cargo-build"));
+
+    let cli_args = CliParameters {
+        projectfile: Some(project_file_path),
+        silent_children: None,
+        output: None,
+        quiet: false,
+        verbose: 0,
+        color: None,
+        mode: Mode::Validate,
+    };
+    assert!(run(cli_args).is_ok());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+/// Testing that init mode only overwrites existing project files when forced
+fn init_overwrite_test() {
+    let dir = create_test_dir("overwrite");
+    let project_file_path = dir.join("Setup.json").display().to_string();
+    std::fs::write(&project_file_path, "{}").unwrap();
+
+    let init_result = run(init_args(&project_file_path, ProjectFormat::Json, false));
+    assert!(init_result.is_err());
+
+    let init_result = run(init_args(&project_file_path, ProjectFormat::Json, true));
+    assert!(init_result.is_ok());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,428 @@
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use setup::{
+    events::OutputStream,
+    report::{JobReport, TaskReport, TaskStatus},
+    reporter::{MessageLevel, MultiReporter, Reporter, TaskInfo},
+    Job, JobOptions, LoadedProject, Task, TaskCall,
+};
+
+static JSON_BASE_PATH: &str = "./tests/execution/json/";
+
+#[test]
+/// Testing the planning and execution of a job loaded from a project file
+fn execute_job_from_path_test() {
+    let project_file_path = format!("{}{}", JSON_BASE_PATH, "simple_project.json");
+    let project = LoadedProject::from_path(&project_file_path).unwrap();
+
+    let plan = project.plan_job("job1").unwrap();
+    assert_eq!(plan.steps.len(), 2);
+
+    let report = project.execute_job("job1", &JobOptions::default()).unwrap();
+    assert!(report.success);
+    assert!(report.error.is_none());
+    assert_eq!(report.tasks.len(), 2);
+    assert!(report
+        .tasks
+        .iter()
+        .all(|task| task.status == TaskStatus::Success));
+}
+
+#[test]
+/// Testing that the types of the loaded project can be named by library users
+fn project_types_test() {
+    let project_file_path = format!("{}{}", JSON_BASE_PATH, "simple_project.json");
+    let project = LoadedProject::from_path(&project_file_path).unwrap();
+
+    let job: &Job = &project.project.jobs["job1"];
+    let taskcall: &TaskCall = &job.tasks[1];
+    assert_eq!(
+        taskcall.args.as_deref(),
+        Some(&[String::from("second")][..])
+    );
+    let task: &Task = &project.project.tasks[&taskcall.task];
+    assert_eq!(task.command.as_deref(), Some("echo"));
+}
+
+#[test]
+/// Testing that a failing job is reported instead of returned as an error
+fn execute_failing_job_from_yaml_test() {
+    let content = "
+jobs:
+  job:
+    tasks:
+      - task: fail
+tasks:
+  fail:
+    command: \"false\"
+";
+    let project = LoadedProject::from_yaml_str(content, "./Setup.yaml").unwrap();
+
+    let report = project.execute_job("job", &JobOptions::default()).unwrap();
+    assert!(!report.success);
+    assert!(report.error.is_some());
+    assert_eq!(report.tasks[0].exit_code, Some(1));
+}
+
+#[test]
+/// Testing that invalid projects and unknown jobs are errors
+fn invalid_project_test() {
+    assert!(LoadedProject::from_json_str("{ \"jobs\": {} }", "./Setup.json").is_err());
+
+    let content = r#"{ "jobs": { "job": { "tasks": [{ "task": "missing" }] } }, "tasks": {} }"#;
+    let project = LoadedProject::from_json_str(content, "./Setup.json").unwrap();
+    assert!(!project.diagnostics().is_empty());
+    assert!(project.plan_job("job").is_err());
+    assert!(project
+        .execute_job("unknown", &JobOptions::default())
+        .is_err());
+}
+
+/// Reporter remembering every call as a line
+#[derive(Default)]
+struct RecordingReporter {
+    calls: Mutex<Vec<String>>,
+}
+
+impl Reporter for RecordingReporter {
+    fn job_started(&self, job: &str) {
+        self.calls
+            .lock()
+            .unwrap()
+            .push(format!("job started {}", job));
+    }
+
+    fn output_line(&self, task: &TaskInfo, _stream: OutputStream, line: &str) {
+        let call = format!("output {} {}", task.command, line);
+        self.calls.lock().unwrap().push(call);
+    }
+
+    fn task_finished(&self, task: &TaskInfo, report: &TaskReport) {
+        let call = format!("finished {} {}", task.command, report.status);
+        self.calls.lock().unwrap().push(call);
+    }
+
+    fn service_ready(&self, task: &TaskInfo) {
+        let call = format!("service ready {}", task.task);
+        self.calls.lock().unwrap().push(call);
+    }
+
+    fn service_stopping(&self, task: &TaskInfo) {
+        let call = format!("service stopping {}", task.task);
+        self.calls.lock().unwrap().push(call);
+    }
+
+    fn hook_started(&self, _job: &str, hook: &str) {
+        let call = format!("hook started {}", hook);
+        self.calls.lock().unwrap().push(call);
+    }
+
+    fn hook_finished(&self, _job: &str, hook: &str, error: Option<&str>) {
+        let call = format!("hook finished {} {}", hook, error.is_none());
+        self.calls.lock().unwrap().push(call);
+    }
+
+    fn message(&self, level: MessageLevel, message: &str) {
+        if level == MessageLevel::Error || level == MessageLevel::Warning {
+            let call = format!("message {:?} {}", level, message);
+            self.calls.lock().unwrap().push(call);
+        }
+    }
+
+    fn job_finished(&self, report: &JobReport) {
+        let call = format!("job finished {}", report.success);
+        self.calls.lock().unwrap().push(call);
+    }
+}
+
+#[test]
+/// Testing that all reporters are notified about the execution
+fn multiple_reporters_test() {
+    let content = r#"{
+        "jobs": { "job": { "tasks": [{ "task": "echo", "args": ["hello"] }] } },
+        "tasks": { "echo": { "command": "echo" } }
+    }"#;
+    let project = LoadedProject::from_json_str(content, "./Setup.json").unwrap();
+
+    let first = Arc::new(RecordingReporter::default());
+    let second = Arc::new(RecordingReporter::default());
+    let options = JobOptions::default()
+        .reporter(first.clone())
+        .reporter(second.clone());
+    assert!(project.execute_job("job", &options).unwrap().success);
+
+    let expected = vec![
+        "job started job",
+        "output echo hello hello",
+        "finished echo hello success",
+        "job finished true",
+    ];
+    assert_eq!(*first.calls.lock().unwrap(), expected);
+    assert_eq!(*second.calls.lock().unwrap(), expected);
+}
+
+#[test]
+/// Testing that services, hooks and failures of parallel tasks are reported instead of printed
+fn service_and_hook_events_test() {
+    let content = r#"{
+        "jobs": {
+            "job": {
+                "tasks": [
+                    {
+                        "task": "service",
+                        "service": true,
+                        "ready": { "probe": { "output_matches": "^ready$" } }
+                    },
+                    { "task": "fail" }
+                ],
+                "parallel": true,
+                "on_failure": [{ "task": "fail" }]
+            }
+        },
+        "tasks": {
+            "service": { "command": "sh", "args": ["-c", "echo ready; sleep 60"] },
+            "fail": { "command": "false" }
+        }
+    }"#;
+    let project = LoadedProject::from_json_str(content, "./Setup.json").unwrap();
+
+    let reporter = Arc::new(RecordingReporter::default());
+    let options = JobOptions::default().reporter(reporter.clone());
+    assert!(!project.execute_job("job", &options).unwrap().success);
+
+    let calls = reporter.calls.lock().unwrap();
+    let calls: Vec<&str> = calls
+        .iter()
+        .map(String::as_str)
+        .filter(|call| !call.starts_with("output") && !call.starts_with("finished"))
+        .collect();
+    assert_eq!(
+        calls,
+        [
+            "job started job",
+            "service ready service",
+            "message Error Task \"false\" failed",
+            "hook started on_failure",
+            "hook finished on_failure false",
+            "service stopping service",
+            "job finished false",
+        ]
+    );
+}
+
+/// Reporter creating a marker file for each reported output line and finished task of labeled steps,
+/// which the commands of the steps wait for
+struct MarkerReporter {
+    dir: PathBuf,
+}
+
+impl Reporter for MarkerReporter {
+    fn output_line(&self, task: &TaskInfo, _stream: OutputStream, line: &str) {
+        if task.label.is_some() {
+            std::fs::write(self.dir.join(line), "").unwrap();
+        }
+    }
+
+    fn task_finished(&self, task: &TaskInfo, _report: &TaskReport) {
+        if let Some(label) = &task.label {
+            std::fs::write(self.dir.join(format!("finished-{}", label)), "").unwrap();
+        }
+    }
+}
+
+/// Returns a shell command waiting until the marker file exists
+fn wait_for_marker(marker: &str) -> String {
+    format!("until [ -e {} ]; do sleep 0.01; done", marker)
+}
+
+/// Returns the last word of the output lines reported while executing the job of the project with the given
+/// output mode. The steps wait for the reported lines of each other, so the order does not depend on timing.
+fn reported_output(output_mode: &str) -> Vec<String> {
+    let dir = std::env::temp_dir().join(format!(
+        "setup_output_{}_{}",
+        output_mode,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    // Grouped lines are reported once the task finished, so the second step waits for the first one
+    let (script_a, script_b) = if output_mode == "interleaved" {
+        (
+            format!("echo a1; {}; echo a2", wait_for_marker("b1")),
+            format!(
+                "{}; echo b1; {}; echo b2; exit 1",
+                wait_for_marker("a1"),
+                wait_for_marker("a2")
+            ),
+        )
+    } else {
+        (
+            String::from("echo a1; echo a2"),
+            format!(
+                "{}; echo b1; echo b2; exit 1",
+                wait_for_marker("finished-a")
+            ),
+        )
+    };
+    let content = format!(
+        r#"{{
+            "settings": {{ "project_file_as_work_dir": true }},
+            "jobs": {{
+                "job": {{
+                    "tasks": [
+                        {{ "task": "sh", "args": ["{}"], "label": "a" }},
+                        {{ "task": "sh", "args": ["{}"], "label": "b" }},
+                        {{ "task": "sh", "args": ["yes line | head -n 300000"] }}
+                    ],
+                    "parallel": true,
+                    "output_mode": "{}"
+                }}
+            }},
+            "tasks": {{ "sh": {{ "command": "sh", "args": ["-c"] }} }}
+        }}"#,
+        script_a, script_b, output_mode
+    );
+    let project = LoadedProject::from_json_str(&content, dir.join("Setup.json")).unwrap();
+
+    let recorder = Arc::new(RecordingReporter::default());
+    let markers = Arc::new(MarkerReporter { dir: dir.clone() });
+    let reporter = MultiReporter::new(vec![recorder.clone(), markers]);
+    let options = JobOptions::default().reporter(Arc::new(reporter));
+    assert!(!project.execute_job("job", &options).unwrap().success);
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let calls = recorder.calls.lock().unwrap();
+    calls
+        .iter()
+        .filter_map(|call| call.strip_prefix("output "))
+        .map(|line| line.rsplit(' ').next().unwrap().to_string())
+        .collect()
+}
+
+/// Returns the reported lines of the short tasks
+fn short_task_lines(lines: &[String]) -> Vec<&str> {
+    lines
+        .iter()
+        .map(String::as_str)
+        .filter(|line| *line != "line")
+        .collect()
+}
+
+#[test]
+/// Testing that grouped output reports the lines of each task as one block
+fn grouped_output_test() {
+    let lines = reported_output("interleaved");
+    assert_eq!(short_task_lines(&lines), ["a1", "b1", "a2", "b2"]);
+
+    // The large output of the third task is moved to a temporary file
+    let lines = reported_output("grouped");
+    assert_eq!(short_task_lines(&lines), ["a1", "a2", "b1", "b2"]);
+    assert_eq!(lines.len(), 300004);
+
+    let lines = reported_output("grouped-on-failure");
+    assert_eq!(lines, ["b1", "b2"]);
+}
+
+#[test]
+/// Testing that step labels override task labels and are combined with matrix values
+fn labels_test() {
+    let content = r#"{
+        "jobs": {
+            "job": {
+                "tasks": [
+                    { "task": "echo", "args": ["a"] },
+                    { "task": "echo", "args": ["b"], "label": "step" },
+                    { "task": "echo", "args": ["${matrix.value}"], "matrix": { "variables": { "value": ["c"] } } }
+                ]
+            }
+        },
+        "tasks": { "echo": { "command": "echo", "label": "print" } }
+    }"#;
+    let project = LoadedProject::from_json_str(content, "./Setup.json").unwrap();
+
+    let report = project.execute_job("job", &JobOptions::default()).unwrap();
+    let labels: Vec<Option<&str>> = report
+        .tasks
+        .iter()
+        .map(|task| task.label.as_deref())
+        .collect();
+    assert_eq!(
+        labels,
+        [Some("print"), Some("step"), Some("print, value=c")]
+    );
+}
+
+#[test]
+/// Testing that a set interrupt flag stops the job before its first step, while the `finally` hook still runs
+/// including its waits
+fn interrupt_flag_test() {
+    let content = r#"{
+        "jobs": {
+            "job": {
+                "tasks": [{ "task": "echo", "args": ["main"] }],
+                "finally": [{ "task": "echo", "args": ["cleanup"] }, { "task": "wait" }]
+            },
+            "parallel_job": {
+                "tasks": [{ "task": "echo", "args": ["main"] }, { "task": "sleep" }],
+                "parallel": true
+            }
+        },
+        "tasks": {
+            "echo": { "command": "echo" },
+            "sleep": { "command": "sleep", "args": ["30"] },
+            "wait": { "wait_for": { "condition": { "command": { "command": "true" } } } }
+        }
+    }"#;
+    let project = LoadedProject::from_json_str(content, "./Setup.json").unwrap();
+
+    let options = JobOptions::default().interrupt(Arc::new(AtomicBool::new(true)));
+    let report = project.execute_job("job", &options).unwrap();
+    assert!(!report.success);
+    assert_eq!(report.error.as_deref(), Some("Execution was interrupted"));
+    assert!(report.tasks[0].status == TaskStatus::NotStarted);
+    assert_eq!(report.hooks.len(), 1);
+    assert_eq!(report.hooks[0].hook, "finally");
+    assert!(report.hooks[0].success);
+    assert_eq!(report.hooks[0].tasks.len(), 2);
+
+    // The hook interrupt flag also stops the hooks, like a second Ctrl+C
+    let options = JobOptions::default()
+        .interrupt(Arc::new(AtomicBool::new(true)))
+        .hook_interrupt(Arc::new(AtomicBool::new(true)));
+    let report = project.execute_job("job", &options).unwrap();
+    assert!(!report.hooks[0].success);
+
+    // Other executions are not affected by the flag
+    let report = project.execute_job("job", &JobOptions::default()).unwrap();
+    assert!(report.success);
+
+    // Parallel tasks are not started once the flag is set
+    let options = JobOptions::default().interrupt(Arc::new(AtomicBool::new(true)));
+    let report = project.execute_job("parallel_job", &options).unwrap();
+    assert_eq!(report.error.as_deref(), Some("Execution was interrupted"));
+    assert!(report
+        .tasks
+        .iter()
+        .all(|task| task.status == TaskStatus::NotStarted));
+
+    // Running parallel tasks are terminated once the flag is set
+    let interrupt = Arc::new(AtomicBool::new(false));
+    let flag = interrupt.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(300));
+        flag.store(true, Ordering::Relaxed);
+    });
+    let start_time = std::time::Instant::now();
+    let options = JobOptions::default().interrupt(interrupt);
+    let report = project.execute_job("parallel_job", &options).unwrap();
+    assert!(start_time.elapsed().as_secs() < 10);
+    assert_eq!(report.error.as_deref(), Some("Execution was interrupted"));
+    assert!(report.tasks[1].status == TaskStatus::Failed);
+}
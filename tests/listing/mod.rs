@@ -0,0 +1,98 @@
+use setup::{
+    cli::{CliParameters, Mode},
+    run,
+};
+
+use crate::json_output;
+
+static JSON_BASE_PATH: &str = "./tests/listing/json/";
+
+#[test]
+/// Testing the listing of jobs filtered by tag
+fn list_jobs_with_tag_test() {
+    let project_file_path = format!("{}{}", JSON_BASE_PATH, "described_project.json");
+
+    let cli_args = CliParameters {
+        projectfile: Some(project_file_path),
+        silent_children: None,
+        output: None,
+        quiet: false,
+        verbose: 0,
+        color: None,
+        mode: Mode::ListJobs {
+            tag: Some(String::from("ci")),
+        },
+    };
+
+    let listing_result = run(cli_args);
+    assert!(listing_result.is_ok())
+}
+
+#[test]
+/// Testing the listing of all tasks including hidden ones
+fn list_all_tasks_test() {
+    let project_file_path = format!("{}{}", JSON_BASE_PATH, "described_project.json");
+
+    let cli_args = CliParameters {
+        projectfile: Some(project_file_path),
+        silent_children: None,
+        output: None,
+        quiet: false,
+        verbose: 0,
+        color: None,
+        mode: Mode::ListTasks {
+            tag: None,
+            all: true,
+        },
+    };
+
+    let listing_result = run(cli_args);
+    assert!(listing_result.is_ok())
+}
+
+/// Returns the names of the listed entries
+fn entry_names(entries: &serde_json::Value) -> Vec<&str> {
+    entries
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry["name"].as_str().unwrap())
+        .collect()
+}
+
+#[test]
+/// Testing the json listing of jobs, sorted by name and filtered by tag
+fn list_jobs_json_test() {
+    let project_file_path = format!("{}{}", JSON_BASE_PATH, "described_project.json");
+
+    let jobs = json_output(&project_file_path, &["list-jobs"]);
+    assert_eq!(entry_names(&jobs), vec!["build", "clean"]);
+    assert_eq!(jobs[0]["description"], "Builds the project");
+    assert_eq!(jobs[0]["tasks"], serde_json::json!(["prepare", "compile"]));
+
+    let ci_jobs = json_output(&project_file_path, &["list-jobs", "--tag", "ci"]);
+    assert_eq!(entry_names(&ci_jobs), vec!["build"]);
+}
+
+#[test]
+/// Testing the json listing of tasks, sorted by name, filtered by tag and without hidden tasks
+fn list_tasks_json_test() {
+    let project_file_path = format!("{}{}", JSON_BASE_PATH, "described_project.json");
+
+    let tasks = json_output(&project_file_path, &["list-tasks"]);
+    assert_eq!(entry_names(&tasks), vec!["compile", "remove"]);
+
+    let all_tasks = json_output(&project_file_path, &["list-tasks", "--all"]);
+    assert_eq!(
+        entry_names(&all_tasks),
+        vec!["compile", "prepare", "remove"]
+    );
+    assert_eq!(all_tasks[1]["hidden"], true);
+    assert_eq!(all_tasks[1]["args"], serde_json::json!(["-p", "out"]));
+
+    let build_tasks = json_output(
+        &project_file_path,
+        &["list-tasks", "--all", "--tag", "build"],
+    );
+    assert_eq!(entry_names(&build_tasks), vec!["compile"]);
+}
@@ -13,6 +13,10 @@ fn correct_jobs_test() {
     let cli_args = CliParameters {
         projectfile: Some(project_file_path),
         silent_children: None,
+        output: None,
+        quiet: false,
+        verbose: 0,
+        color: None,
         mode: Mode::Validate,
     };
 
@@ -28,6 +32,10 @@ fn missing_task_in_taskcall_test() {
     let cli_args = CliParameters {
         projectfile: Some(project_file_path),
         silent_children: None,
+        output: None,
+        quiet: false,
+        verbose: 0,
+        color: None,
         mode: Mode::Validate,
     };
 
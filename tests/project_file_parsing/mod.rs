@@ -1,4 +1,5 @@
 mod jobs;
+mod semantics;
 mod settings;
 mod tasks;
 
@@ -18,6 +19,29 @@ fn minimal_project_file_test() {
         projectfile: Some(project_file_path),
         mode: Mode::Validate,
         silent_children: None,
+        output: None,
+        quiet: false,
+        verbose: 0,
+        color: None,
+    };
+
+    let validation_result = run(cli_args);
+    assert!(validation_result.is_ok())
+}
+
+#[test]
+/// Test the parsing of a minimal project file in YAML format
+fn minimal_yaml_project_file_test() {
+    let project_file_path = format!("{}{}", JSON_BASE_PATH, "minimal_project_file.yaml");
+
+    let cli_args = CliParameters {
+        projectfile: Some(project_file_path),
+        mode: Mode::Validate,
+        silent_children: None,
+        output: None,
+        quiet: false,
+        verbose: 0,
+        color: None,
     };
 
     let validation_result = run(cli_args);
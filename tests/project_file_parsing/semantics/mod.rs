@@ -0,0 +1,160 @@
+use setup::{
+    cli::{CliParameters, Mode, OutputFormat},
+    run, DiagnosticSeverity, LoadedProject,
+};
+
+static JSON_BASE_PATH: &str = "./tests/project_file_parsing/semantics/json/";
+
+#[test]
+/// Testing a project file with a job referencing an unknown task
+fn unknown_task_test() {
+    let project_file_path = format!("{}{}", JSON_BASE_PATH, "unknown_task.json");
+
+    let cli_args = CliParameters {
+        projectfile: Some(project_file_path),
+        silent_children: None,
+        output: None,
+        quiet: false,
+        verbose: 0,
+        color: None,
+        mode: Mode::Validate,
+    };
+
+    let validation_result = run(cli_args);
+    assert!(validation_result.is_err())
+}
+
+#[test]
+/// Testing a project file with a taskcall passing the wrong amount of arguments
+fn wrong_argument_count_test() {
+    let project_file_path = format!("{}{}", JSON_BASE_PATH, "wrong_argument_count.json");
+
+    let cli_args = CliParameters {
+        projectfile: Some(project_file_path),
+        silent_children: None,
+        output: None,
+        quiet: false,
+        verbose: 0,
+        color: None,
+        mode: Mode::Validate,
+    };
+
+    let validation_result = run(cli_args);
+    assert!(validation_result.is_err())
+}
+
+#[test]
+/// Testing a project file with a job without any tasks
+fn empty_job_test() {
+    let project_file_path = format!("{}{}", JSON_BASE_PATH, "empty_job.json");
+
+    let cli_args = CliParameters {
+        projectfile: Some(project_file_path),
+        silent_children: None,
+        output: None,
+        quiet: false,
+        verbose: 0,
+        color: None,
+        mode: Mode::Validate,
+    };
+
+    let validation_result = run(cli_args);
+    assert!(validation_result.is_err())
+}
+
+#[test]
+/// Testing a project file with duplicated steps and unused tasks, which only result in warnings
+fn warnings_only_test() {
+    let project_file_path = format!("{}{}", JSON_BASE_PATH, "warnings_only.json");
+
+    let cli_args = CliParameters {
+        projectfile: Some(project_file_path),
+        silent_children: None,
+        output: None,
+        quiet: false,
+        verbose: 0,
+        color: None,
+        mode: Mode::Validate,
+    };
+
+    let validation_result = run(cli_args);
+    assert!(validation_result.is_ok())
+}
+
+#[test]
+/// Testing that json output of validate mode still fails for invalid project files
+fn unknown_task_json_output_test() {
+    let project_file_path = format!("{}{}", JSON_BASE_PATH, "unknown_task.json");
+
+    let cli_args = CliParameters {
+        projectfile: Some(project_file_path),
+        silent_children: None,
+        output: Some(OutputFormat::Json),
+        quiet: false,
+        verbose: 0,
+        color: None,
+        mode: Mode::Validate,
+    };
+
+    let validation_result = run(cli_args);
+    assert!(validation_result.is_err())
+}
+
+#[test]
+/// Testing a project file with a matrix excluding an unknown variable
+fn unknown_matrix_variable_test() {
+    let project_file_path = format!("{}{}", JSON_BASE_PATH, "unknown_matrix_variable.json");
+
+    let cli_args = CliParameters {
+        projectfile: Some(project_file_path),
+        silent_children: None,
+        output: None,
+        quiet: false,
+        verbose: 0,
+        color: None,
+        mode: Mode::Validate,
+    };
+
+    let validation_result = run(cli_args);
+    assert!(validation_result.is_err())
+}
+
+#[test]
+/// Testing that steps calling a task with the same arguments are only duplicates if their conditions are equal
+fn conditional_duplicates_test() {
+    let project_file_path = format!("{}{}", JSON_BASE_PATH, "conditional_duplicates.json");
+
+    let project = LoadedProject::from_path(project_file_path).unwrap();
+    let warnings: Vec<String> = project
+        .diagnostics()
+        .into_iter()
+        .filter(|diagnostic| diagnostic.severity == DiagnosticSeverity::Warning)
+        .map(|diagnostic| diagnostic.message)
+        .collect();
+    assert_eq!(
+        warnings,
+        vec![String::from(
+            "Job \"job1\" calls task \"echo\" with the same arguments more than once (step 4)"
+        )]
+    );
+}
+
+#[test]
+/// Testing that steps calling a task with the same arguments are no duplicates if their label, matrix or service differ
+fn distinct_steps_test() {
+    let project_file_path = format!("{}{}", JSON_BASE_PATH, "distinct_steps.json");
+
+    let project = LoadedProject::from_path(project_file_path).unwrap();
+    let warnings: Vec<String> = project
+        .diagnostics()
+        .into_iter()
+        .filter(|diagnostic| diagnostic.severity == DiagnosticSeverity::Warning)
+        .map(|diagnostic| diagnostic.message)
+        .collect();
+    assert_eq!(
+        warnings,
+        vec![String::from(
+            "Job \"job1\" calls task \"echo\" with the same arguments more than once (step 6)"
+        )]
+    );
+}
@@ -13,6 +13,10 @@ fn empty_settings_test() {
     let cli_args = CliParameters {
         projectfile: Some(project_file_path),
         silent_children: None,
+        output: None,
+        quiet: false,
+        verbose: 0,
+        color: None,
         mode: Mode::Validate,
     };
 
@@ -28,6 +32,10 @@ fn complete_settings_test() {
     let cli_args = CliParameters {
         projectfile: Some(project_file_path),
         silent_children: None,
+        output: None,
+        quiet: false,
+        verbose: 0,
+        color: None,
         mode: Mode::Validate,
     };
 
@@ -43,6 +51,29 @@ fn wrong_settings_entry_test() {
     let cli_args = CliParameters {
         projectfile: Some(project_file_path),
         silent_children: None,
+        output: None,
+        quiet: false,
+        verbose: 0,
+        color: None,
+        mode: Mode::Validate,
+    };
+
+    let validation_result = run(cli_args);
+    assert!(validation_result.is_err())
+}
+
+#[test]
+/// Testing a project file with an unknown placeholder in the output prefix template
+fn invalid_output_prefix_test() {
+    let project_file_path = format!("{}{}", JSON_BASE_PATH, "invalid_output_prefix.json");
+
+    let cli_args = CliParameters {
+        projectfile: Some(project_file_path),
+        silent_children: None,
+        output: None,
+        quiet: false,
+        verbose: 0,
+        color: None,
         mode: Mode::Validate,
     };
 
@@ -1,6 +1,6 @@
 use setup::{
     cli::{CliParameters, Mode},
-    run,
+    run, LoadedProject,
 };
 
 static JSON_BASE_PATH: &str = "./tests/project_file_parsing/tasks/json/";
@@ -13,6 +13,10 @@ fn correct_tasks_test() {
     let cli_args = CliParameters {
         projectfile: Some(project_file_path),
         silent_children: None,
+        output: None,
+        quiet: false,
+        verbose: 0,
+        color: None,
         mode: Mode::Validate,
     };
 
@@ -28,13 +32,45 @@ fn missing_command_test() {
     let cli_args = CliParameters {
         projectfile: Some(project_file_path),
         silent_children: None,
+        output: None,
+        quiet: false,
+        verbose: 0,
+        color: None,
         mode: Mode::Validate,
     };
 
     let validation_result = run(cli_args);
-    match &validation_result {
-        Err(err) => println!("{}", err),
-        _ => {}
+    if let Err(err) = &validation_result {
+        println!("{}", err);
     }
     assert!(validation_result.is_err())
 }
+
+#[test]
+/// Testing that the schema rejects tasks without or with several operations
+fn task_operation_schema_test() {
+    let project_file_path = format!("{}{}", JSON_BASE_PATH, "missing_command_task.json");
+    assert!(LoadedProject::from_path(project_file_path).is_err());
+
+    let project_file_path = format!("{}{}", JSON_BASE_PATH, "multiple_operations_task.json");
+    assert!(LoadedProject::from_path(project_file_path).is_err());
+}
+
+#[test]
+/// Testing a project file with an unknown task entry
+fn unknown_field_test() {
+    let project_file_path = format!("{}{}", JSON_BASE_PATH, "unknown_field_task.json");
+
+    let cli_args = CliParameters {
+        projectfile: Some(project_file_path),
+        silent_children: None,
+        output: None,
+        quiet: false,
+        verbose: 0,
+        color: None,
+        mode: Mode::Validate,
+    };
+
+    let validation_result = run(cli_args);
+    assert!(validation_result.is_err())
+}
@@ -1 +1,18 @@
+mod cli;
+mod completions;
+mod execution;
+mod init;
+mod library;
+mod listing;
 mod project_file_parsing;
+
+/// Runs the `setup` binary with json output and parses what it printed to stdout
+pub fn json_output(projectfile: &str, args: &[&str]) -> serde_json::Value {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_setup"))
+        .arg(projectfile)
+        .args(["--output", "json"])
+        .args(args)
+        .output()
+        .unwrap();
+    serde_json::from_slice(&output.stdout).unwrap()
+}